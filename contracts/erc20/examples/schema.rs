@@ -4,7 +4,9 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use cw_erc20::{
-    AllowanceResponse, BalanceResponse, Constants, ExecuteMsg, InstantiateMsg, QueryMsg,
+    AllowanceResponse, BalanceResponse, Constants, DownloadLogoResponse, ExecuteMsg,
+    FeeConfigResponse, InstantiateMsg, MarketingInfoResponse, MigrateMsg, QueryMsg,
+    TransferFeeResponse, VestingScheduleResponse,
 };
 
 fn main() {
@@ -16,7 +18,13 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(BalanceResponse), &out_dir);
     export_schema(&schema_for!(AllowanceResponse), &out_dir);
+    export_schema(&schema_for!(VestingScheduleResponse), &out_dir);
+    export_schema(&schema_for!(MarketingInfoResponse), &out_dir);
+    export_schema(&schema_for!(DownloadLogoResponse), &out_dir);
+    export_schema(&schema_for!(FeeConfigResponse), &out_dir);
+    export_schema(&schema_for!(TransferFeeResponse), &out_dir);
     export_schema(&schema_for!(Constants), &out_dir);
 }
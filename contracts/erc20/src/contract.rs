@@ -1,37 +1,49 @@
 use cosmwasm_std::{
-    entry_point, to_binary, to_vec, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Storage, Uint128,
+    entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Storage, Timestamp, Uint128,
 };
-use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
-use std::convert::TryInto;
+use cw2::{set_contract_version, CONTRACT};
+use semver::Version;
 
 use crate::error::ContractError;
-use crate::msg::{AllowanceResponse, BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::Constants;
+use crate::legacy::migrate_legacy_storage;
+use crate::msg::{
+    AllowanceResponse, BalanceResponse, DownloadLogoResponse, EmbeddedLogo, ExecuteMsg,
+    FeeConfigResponse, InstantiateMsg, Logo, MarketingInfoResponse, MigrateMsg, QueryMsg,
+    TransferFee, TransferFeeResponse, VestingScheduleResponse,
+};
+use crate::state::{
+    Constants, FeeConfig, LogoInfo, MarketingInfo, VestingSchedule, ADMIN, ALLOWANCES, BALANCES,
+    CONSTANTS, FEE_CONFIG, LOGO, MARKETING_INFO, TOTAL_SUPPLY, VESTING,
+};
 
-pub const PREFIX_CONFIG: &[u8] = b"config";
-pub const PREFIX_BALANCES: &[u8] = b"balances";
-pub const PREFIX_ALLOWANCES: &[u8] = b"allowances";
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:cw-erc20";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-pub const KEY_CONSTANTS: &[u8] = b"constants";
-pub const KEY_TOTAL_SUPPLY: &[u8] = b"total_supply";
+const LOGO_SIZE_CAP: usize = 5 * 1024;
 
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    let mut total_supply: u128 = 0;
-    {
-        // Initial balances
-        let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
-        for row in msg.initial_balances {
-            let amount_raw = row.amount.u128();
-            balances_store.set(row.address.as_str().as_bytes(), &amount_raw.to_be_bytes());
-            total_supply += amount_raw;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let mut total_supply = Uint128::zero();
+    // Initial balances
+    for row in msg.initial_balances {
+        let address = deps.api.addr_validate(row.address.as_str())?;
+        if let Some(schedule) = row.vesting {
+            if !schedule.is_valid() || schedule.total > row.amount {
+                return Err(ContractError::InvalidVestingSchedule {});
+            }
+            VESTING.save(deps.storage, &address, &schedule)?;
         }
+        BALANCES.save(deps.storage, &address, &row.amount)?;
+        total_supply += row.amount;
     }
 
     // Check name, symbol, decimals
@@ -45,18 +57,82 @@ pub fn instantiate(
         return Err(ContractError::DecimalsExceeded {});
     }
 
-    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
-    let constants = to_vec(&Constants {
-        name: msg.name,
-        symbol: msg.symbol,
-        decimals: msg.decimals,
-    })?;
-    config_store.set(KEY_CONSTANTS, &constants);
-    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+    if let Some(marketing) = msg.marketing {
+        let logo = match marketing.logo {
+            Some(logo) => {
+                verify_logo(&logo)?;
+                LOGO.save(deps.storage, &logo)?;
+                Some(logo_info(&logo))
+            }
+            None => None,
+        };
+        let marketing_info = MarketingInfo {
+            project: marketing.project,
+            description: marketing.description,
+            marketing: marketing
+                .marketing
+                .map(|address| deps.api.addr_validate(&address))
+                .transpose()?,
+            logo,
+        };
+        MARKETING_INFO.save(deps.storage, &marketing_info)?;
+    }
+
+    CONSTANTS.save(
+        deps.storage,
+        &Constants {
+            name: msg.name,
+            symbol: msg.symbol,
+            decimals: msg.decimals,
+        },
+    )?;
+    TOTAL_SUPPLY.save(deps.storage, &total_supply)?;
+    ADMIN.save(deps.storage, &info.sender)?;
 
     Ok(Response::default())
 }
 
+/// Upgrade to the current contract version
+///
+/// Contracts instantiated before cw2 was introduced have their hand-rolled storage
+/// rewritten into the typed storage of `state.rs`. They never stored an admin, so
+/// `msg.admin` is required for them. Downgrades are refused.
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let new_version: Version = CONTRACT_VERSION.parse()?;
+    match CONTRACT.may_load(deps.storage)? {
+        Some(stored) => {
+            if stored.contract != CONTRACT_NAME {
+                return Err(ContractError::WrongContract {
+                    expected: CONTRACT_NAME.to_string(),
+                    found: stored.contract,
+                });
+            }
+            let stored_version: Version = stored.version.parse()?;
+            if stored_version > new_version {
+                return Err(ContractError::CannotDowngrade {
+                    stored: stored.version,
+                    new: CONTRACT_VERSION.to_string(),
+                });
+            }
+        }
+        None => {
+            if msg.admin.is_none() {
+                return Err(ContractError::MissingAdmin {});
+            }
+            migrate_legacy_storage(deps.storage)?;
+        }
+    }
+    if let Some(admin) = msg.admin {
+        ADMIN.save(deps.storage, &deps.api.addr_validate(&admin)?)?;
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("version", CONTRACT_VERSION))
+}
+
 #[entry_point]
 pub fn execute(
     deps: DepsMut,
@@ -69,32 +145,111 @@ pub fn execute(
         ExecuteMsg::Transfer { recipient, amount } => {
             try_transfer(deps, env, info, recipient, &amount)
         }
+        ExecuteMsg::BatchTransfer { transfers } => try_batch_transfer(deps, env, info, transfers),
         ExecuteMsg::TransferFrom {
             owner,
             recipient,
             amount,
         } => try_transfer_from(deps, env, info, owner, recipient, &amount),
         ExecuteMsg::Burn { amount } => try_burn(deps, env, info, &amount),
+        ExecuteMsg::CreateVestingSchedule { address, schedule } => {
+            try_create_vesting_schedule(deps, env, info, address, schedule)
+        }
+        ExecuteMsg::UpdateMarketing {
+            project,
+            description,
+            marketing,
+        } => try_update_marketing(deps, env, info, project, description, marketing),
+        ExecuteMsg::UploadLogo(logo) => try_upload_logo(deps, env, info, logo),
+        ExecuteMsg::UpdateFeeConfig { fee } => try_update_fee_config(deps, env, info, fee),
     }
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Balance { address } => {
             let address_key = deps.api.addr_validate(&address)?;
-            let balance = read_balance(deps.storage, &address_key)?;
-            let out = to_binary(&BalanceResponse {
-                balance: Uint128::from(balance),
-            })?;
+            let balance = BALANCES
+                .may_load(deps.storage, &address_key)?
+                .unwrap_or_default();
+            let out = to_binary(&BalanceResponse { balance })?;
             Ok(out)
         }
         QueryMsg::Allowance { owner, spender } => {
             let owner_key = deps.api.addr_validate(&owner)?;
             let spender_key = deps.api.addr_validate(&spender)?;
-            let allowance = read_allowance(deps.storage, &owner_key, &spender_key)?;
-            let out = to_binary(&AllowanceResponse {
-                allowance: Uint128::from(allowance),
+            let allowance = ALLOWANCES
+                .may_load(deps.storage, (&owner_key, &spender_key))?
+                .unwrap_or_default();
+            let out = to_binary(&AllowanceResponse { allowance })?;
+            Ok(out)
+        }
+        QueryMsg::VestingSchedule { address } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let schedule = VESTING.may_load(deps.storage, &address_key)?;
+            let (locked, unlocked) = match &schedule {
+                Some(schedule) => (
+                    schedule.locked_amount(env.block.time),
+                    schedule.vested_amount(env.block.time),
+                ),
+                None => (Uint128::zero(), Uint128::zero()),
+            };
+            let out = to_binary(&VestingScheduleResponse {
+                schedule,
+                locked,
+                unlocked,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::MarketingInfo {} => {
+            let marketing_info = MARKETING_INFO.may_load(deps.storage)?.unwrap_or_default();
+            let out = to_binary(&MarketingInfoResponse {
+                project: marketing_info.project,
+                description: marketing_info.description,
+                logo: marketing_info.logo,
+                marketing: marketing_info.marketing,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::DownloadLogo {} => {
+            let out = match LOGO.may_load(deps.storage)? {
+                Some(Logo::Embedded(EmbeddedLogo::Svg(data))) => {
+                    to_binary(&DownloadLogoResponse {
+                        mime_type: "image/svg+xml".to_string(),
+                        data,
+                    })?
+                }
+                Some(Logo::Embedded(EmbeddedLogo::Png(data))) => {
+                    to_binary(&DownloadLogoResponse {
+                        mime_type: "image/png".to_string(),
+                        data,
+                    })?
+                }
+                Some(Logo::Url(_)) | None => return Err(StdError::not_found("logo").into()),
+            };
+            Ok(out)
+        }
+        QueryMsg::FeeConfig {} => {
+            let out = to_binary(&FeeConfigResponse {
+                fee_config: FEE_CONFIG.may_load(deps.storage)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::TransferFee {
+            sender,
+            recipient,
+            amount,
+        } => {
+            let sender_key = deps.api.addr_validate(&sender)?;
+            let recipient_key = deps.api.addr_validate(&recipient)?;
+            let fee = match FEE_CONFIG.may_load(deps.storage)? {
+                Some(fee_config) => fee_config.fee_for(&sender_key, &recipient_key, amount),
+                None => Uint128::zero(),
+            };
+            let out = to_binary(&TransferFeeResponse {
+                fee,
+                net_amount: amount - fee,
             })?;
             Ok(out)
         }
@@ -103,7 +258,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
 
 fn try_transfer(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     recipient: String,
     amount: &Uint128,
@@ -113,6 +268,7 @@ fn try_transfer(
         &info.sender,
         &deps.api.addr_validate(recipient.as_str())?,
         amount.u128(),
+        env.block.time,
     )?;
     Ok(Response::new()
         .add_attribute("action", "transfer")
@@ -120,9 +276,53 @@ fn try_transfer(
         .add_attribute("recipient", recipient))
 }
 
+/// Transfer tokens to many recipients at once
+///
+/// All recipients are validated and the total is checked against the sender balance
+/// before any balance is touched, so the batch either applies completely or not at all.
+fn try_batch_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    transfers: Vec<(String, Uint128)>,
+) -> Result<Response, ContractError> {
+    if transfers.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+
+    let mut validated = Vec::with_capacity(transfers.len());
+    let mut total = Uint128::zero();
+    for (recipient, amount) in transfers {
+        let recipient_address = deps.api.addr_validate(recipient.as_str())?;
+        total = total.checked_add(amount).map_err(StdError::from)?;
+        validated.push((recipient_address, amount));
+    }
+
+    let balance = read_balance(deps.storage, &info.sender)?;
+    let locked = read_locked(deps.storage, &info.sender, env.block.time)?;
+    check_spendable(balance.u128(), locked.u128(), total.u128())?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "batch_transfer")
+        .add_attribute("sender", &info.sender);
+    for (recipient_address, amount) in validated {
+        perform_transfer(
+            deps.storage,
+            &info.sender,
+            &recipient_address,
+            amount.u128(),
+            env.block.time,
+        )?;
+        response = response
+            .add_attribute("recipient", recipient_address)
+            .add_attribute("amount", amount.to_string());
+    }
+    Ok(response)
+}
+
 fn try_transfer_from(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     owner: String,
     recipient: String,
@@ -132,16 +332,30 @@ fn try_transfer_from(
     let recipient_address = deps.api.addr_validate(recipient.as_str())?;
     let amount_raw = amount.u128();
 
-    let mut allowance = read_allowance(deps.storage, &owner_address, &info.sender)?;
+    let mut allowance = ALLOWANCES
+        .may_load(deps.storage, (&owner_address, &info.sender))?
+        .unwrap_or_default()
+        .u128();
     if allowance < amount_raw {
         return Err(ContractError::InsufficientAllowance {
             allowance,
             required: amount_raw,
         });
     }
+    // The allowance is charged the gross amount, including any transfer fee
     allowance -= amount_raw;
-    write_allowance(deps.storage, &owner_address, &info.sender, allowance)?;
-    perform_transfer(deps.storage, &owner_address, &recipient_address, amount_raw)?;
+    ALLOWANCES.save(
+        deps.storage,
+        (&owner_address, &info.sender),
+        &Uint128::from(allowance),
+    )?;
+    perform_transfer(
+        deps.storage,
+        &owner_address,
+        &recipient_address,
+        amount_raw,
+        env.block.time,
+    )?;
 
     Ok(Response::new()
         .add_attribute("action", "transfer_from")
@@ -158,7 +372,7 @@ fn try_approve(
     amount: &Uint128,
 ) -> Result<Response, ContractError> {
     let spender_address = deps.api.addr_validate(spender.as_str())?;
-    write_allowance(deps.storage, &info.sender, &spender_address, amount.u128())?;
+    ALLOWANCES.save(deps.storage, (&info.sender, &spender_address), amount)?;
     Ok(Response::new()
         .add_attribute("action", "approve")
         .add_attribute("owner", info.sender)
@@ -172,120 +386,280 @@ fn try_approve(
 /// @param amount the amount of money to burn
 fn try_burn(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     amount: &Uint128,
 ) -> Result<Response, ContractError> {
-    let amount_raw = amount.u128();
+    debit_balance(deps.storage, &info.sender, amount.u128(), env.block.time)?;
+    TOTAL_SUPPLY.update(deps.storage, |total_supply| -> StdResult<_> {
+        Ok(total_supply.checked_sub(*amount)?)
+    })?;
 
-    let mut account_balance = read_balance(deps.storage, &info.sender)?;
+    Ok(Response::new()
+        .add_attribute("action", "burn")
+        .add_attribute("account", info.sender)
+        .add_attribute("amount", amount.to_string()))
+}
 
-    if account_balance < amount_raw {
-        return Err(ContractError::InsufficientFunds {
-            balance: account_balance,
-            required: amount_raw,
-        });
+/// Admin only. Moves `schedule.total` from the admin to `address` and locks it there
+fn try_create_vesting_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    schedule: VestingSchedule,
+) -> Result<Response, ContractError> {
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !schedule.is_valid() {
+        return Err(ContractError::InvalidVestingSchedule {});
+    }
+    let address_key = deps.api.addr_validate(address.as_str())?;
+    if let Some(existing) = VESTING.may_load(deps.storage, &address_key)? {
+        if !existing.locked_amount(env.block.time).is_zero() {
+            return Err(ContractError::VestingScheduleExists {});
+        }
+    }
+
+    // Vested tokens are moved without a transfer fee so the full total can be locked
+    debit_balance(
+        deps.storage,
+        &info.sender,
+        schedule.total.u128(),
+        env.block.time,
+    )?;
+    credit_balance(deps.storage, &address_key, schedule.total.u128())?;
+    VESTING.save(deps.storage, &address_key, &schedule)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_vesting_schedule")
+        .add_attribute("admin", info.sender)
+        .add_attribute("address", address)
+        .add_attribute("amount", schedule.total.to_string()))
+}
+
+/// Update marketing info
+///
+/// Only the marketing admin may call this. Fields set to `None` are left unchanged,
+/// fields set to an empty string are cleared.
+fn try_update_marketing(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    project: Option<String>,
+    description: Option<String>,
+    marketing: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut marketing_info = MARKETING_INFO.may_load(deps.storage)?.unwrap_or_default();
+    if marketing_info.marketing.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
     }
-    account_balance -= amount_raw;
 
-    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
-    balances_store.set(
-        info.sender.as_str().as_bytes(),
-        &account_balance.to_be_bytes(),
-    );
+    match project {
+        Some(project) if project.trim().is_empty() => marketing_info.project = None,
+        Some(project) => marketing_info.project = Some(project),
+        None => {}
+    }
+    match description {
+        Some(description) if description.trim().is_empty() => marketing_info.description = None,
+        Some(description) => marketing_info.description = Some(description),
+        None => {}
+    }
+    match marketing {
+        Some(marketing) if marketing.trim().is_empty() => marketing_info.marketing = None,
+        Some(marketing) => marketing_info.marketing = Some(deps.api.addr_validate(&marketing)?),
+        None => {}
+    }
+    MARKETING_INFO.save(deps.storage, &marketing_info)?;
 
-    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
-    let data = config_store
-        .get(KEY_TOTAL_SUPPLY)
-        .expect("no total supply data stored");
-    let mut total_supply = bytes_to_u128(&data).unwrap();
+    Ok(Response::new()
+        .add_attribute("action", "update_marketing")
+        .add_attribute("sender", info.sender))
+}
 
-    total_supply -= amount_raw;
+/// Replace the logo, only the marketing admin may call this
+fn try_upload_logo(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    logo: Logo,
+) -> Result<Response, ContractError> {
+    let mut marketing_info = MARKETING_INFO.may_load(deps.storage)?.unwrap_or_default();
+    if marketing_info.marketing.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    verify_logo(&logo)?;
 
-    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+    LOGO.save(deps.storage, &logo)?;
+    marketing_info.logo = Some(logo_info(&logo));
+    MARKETING_INFO.save(deps.storage, &marketing_info)?;
 
     Ok(Response::new()
-        .add_attribute("action", "burn")
-        .add_attribute("account", info.sender)
-        .add_attribute("amount", amount.to_string()))
+        .add_attribute("action", "upload_logo")
+        .add_attribute("sender", info.sender))
+}
+
+/// Set or remove the transfer fee, only the admin may call this
+fn try_update_fee_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    fee: Option<TransferFee>,
+) -> Result<Response, ContractError> {
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut response = Response::new()
+        .add_attribute("action", "update_fee_config")
+        .add_attribute("admin", &info.sender);
+    match fee {
+        Some(fee) => {
+            if fee.basis_points > 10_000 {
+                return Err(ContractError::FeeTooHigh {});
+            }
+            let fee_config = FeeConfig {
+                basis_points: fee.basis_points,
+                collector: deps.api.addr_validate(&fee.collector)?,
+                exempt: fee
+                    .exempt
+                    .iter()
+                    .map(|address| deps.api.addr_validate(address))
+                    .collect::<StdResult<_>>()?,
+            };
+            FEE_CONFIG.save(deps.storage, &fee_config)?;
+            response = response
+                .add_attribute("basis_points", fee_config.basis_points.to_string())
+                .add_attribute("collector", fee_config.collector);
+        }
+        None => FEE_CONFIG.remove(deps.storage),
+    }
+    Ok(response)
 }
 
+/// Moves `amount` from `from` to `to`, splitting off the transfer fee if one is configured.
+/// The sender is always charged the full `amount`.
 fn perform_transfer(
     store: &mut dyn Storage,
     from: &Addr,
     to: &Addr,
     amount: u128,
+    time: Timestamp,
+) -> Result<(), ContractError> {
+    let fee = match FEE_CONFIG.may_load(store)? {
+        Some(fee_config) => Some((
+            fee_config.fee_for(from, to, Uint128::from(amount)).u128(),
+            fee_config.collector,
+        )),
+        None => None,
+    };
+
+    debit_balance(store, from, amount, time)?;
+    match fee {
+        Some((fee, collector)) if fee > 0 => {
+            credit_balance(store, to, amount - fee)?;
+            credit_balance(store, &collector, fee)?;
+        }
+        _ => credit_balance(store, to, amount)?,
+    }
+    Ok(())
+}
+
+// Removes `amount` from the unlocked balance of `owner`
+fn debit_balance(
+    store: &mut dyn Storage,
+    owner: &Addr,
+    amount: u128,
+    time: Timestamp,
 ) -> Result<(), ContractError> {
-    let mut balances_store = PrefixedStorage::new(store, PREFIX_BALANCES);
+    let balance = read_balance(store, owner)?;
+    let locked = read_locked(store, owner, time)?;
+    check_spendable(balance.u128(), locked.u128(), amount)?;
+    BALANCES.save(store, owner, &(balance - Uint128::from(amount)))?;
+    Ok(())
+}
 
-    let mut from_balance = match balances_store.get(from.as_str().as_bytes()) {
-        Some(data) => bytes_to_u128(&data),
-        None => Ok(0u128),
-    }?;
+fn credit_balance(store: &mut dyn Storage, owner: &Addr, amount: u128) -> StdResult<()> {
+    BALANCES.update(store, owner, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default() + Uint128::from(amount))
+    })?;
+    Ok(())
+}
 
-    if from_balance < amount {
+// Errors if `amount` exceeds the balance, or the part of it not locked by vesting
+fn check_spendable(balance: u128, locked: u128, amount: u128) -> Result<(), ContractError> {
+    if balance < amount {
         return Err(ContractError::InsufficientFunds {
-            balance: from_balance,
+            balance,
             required: amount,
         });
     }
-    from_balance -= amount;
-    balances_store.set(from.as_str().as_bytes(), &from_balance.to_be_bytes());
+    let unlocked = balance.saturating_sub(locked);
+    if unlocked < amount {
+        return Err(ContractError::InsufficientUnlockedFunds {
+            unlocked,
+            required: amount,
+        });
+    }
+    Ok(())
+}
 
-    let mut to_balance = match balances_store.get(to.as_str().as_bytes()) {
-        Some(data) => bytes_to_u128(&data),
-        None => Ok(0u128),
-    }?;
-    to_balance += amount;
-    balances_store.set(to.as_str().as_bytes(), &to_balance.to_be_bytes());
+fn read_balance(store: &dyn Storage, owner: &Addr) -> StdResult<Uint128> {
+    Ok(BALANCES.may_load(store, owner)?.unwrap_or_default())
+}
 
-    Ok(())
+// Returns the part of the balance of `owner` still locked by vesting at `time`
+fn read_locked(store: &dyn Storage, owner: &Addr, time: Timestamp) -> StdResult<Uint128> {
+    Ok(match VESTING.may_load(store, owner)? {
+        Some(schedule) => schedule.locked_amount(time),
+        None => Uint128::zero(),
+    })
 }
 
-// Converts 16 bytes value into u128
-// Errors if data found that is not 16 bytes
-pub fn bytes_to_u128(data: &[u8]) -> Result<u128, ContractError> {
-    match data[0..16].try_into() {
-        Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
-        Err(_) => Err(ContractError::CorruptedDataFound {}),
+fn logo_info(logo: &Logo) -> LogoInfo {
+    match logo {
+        Logo::Url(url) => LogoInfo::Url(url.clone()),
+        Logo::Embedded(_) => LogoInfo::Embedded,
     }
 }
 
-// Reads 16 byte storage value into u128
-// Returns zero if key does not exist. Errors if data found that is not 16 bytes
-pub fn read_u128(store: &ReadonlyPrefixedStorage, key: &Addr) -> Result<u128, ContractError> {
-    let result = store.get(key.as_str().as_bytes());
-    match result {
-        Some(data) => bytes_to_u128(&data),
-        None => Ok(0u128),
+fn verify_logo(logo: &Logo) -> Result<(), ContractError> {
+    match logo {
+        Logo::Embedded(EmbeddedLogo::Svg(data)) => verify_xml_logo(data),
+        Logo::Embedded(EmbeddedLogo::Png(data)) => verify_png_logo(data),
+        Logo::Url(_) => Ok(()),
     }
 }
 
-fn read_balance(store: &dyn Storage, owner: &Addr) -> Result<u128, ContractError> {
-    let balance_store = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES);
-    read_u128(&balance_store, owner)
+// Checks that the SVG starts with an `<?xml ... ?>` preamble and fits the size cap
+fn verify_xml_logo(logo: &[u8]) -> Result<(), ContractError> {
+    const PREFIX: &[u8] = b"<?xml ";
+    const POSTFIX: &[u8] = b"?>";
+
+    let preamble = logo
+        .split_inclusive(|c| *c == b'>')
+        .next()
+        .ok_or(ContractError::InvalidXmlPreamble {})?;
+    if !(preamble.starts_with(PREFIX) && preamble.ends_with(POSTFIX)) {
+        return Err(ContractError::InvalidXmlPreamble {});
+    }
+    if logo.len() > LOGO_SIZE_CAP {
+        return Err(ContractError::LogoTooBig {});
+    }
+    Ok(())
 }
 
-fn read_allowance(
-    store: &dyn Storage,
-    owner: &Addr,
-    spender: &Addr,
-) -> Result<u128, ContractError> {
-    let owner_store =
-        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
-    read_u128(&owner_store, spender)
-}
+// Checks that the PNG starts with the PNG file signature and fits the size cap
+fn verify_png_logo(logo: &[u8]) -> Result<(), ContractError> {
+    const HEADER: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
 
-#[allow(clippy::unnecessary_wraps)]
-fn write_allowance(
-    store: &mut dyn Storage,
-    owner: &Addr,
-    spender: &Addr,
-    amount: u128,
-) -> StdResult<()> {
-    let mut owner_store =
-        PrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
-    owner_store.set(spender.as_str().as_bytes(), &amount.to_be_bytes());
+    if logo.len() > LOGO_SIZE_CAP {
+        return Err(ContractError::LogoTooBig {});
+    }
+    if !logo.starts_with(&HEADER) {
+        return Err(ContractError::InvalidPngHeader {});
+    }
     Ok(())
 }
 
@@ -316,7 +690,6 @@ mod tests {
     use crate::msg::InitialBalance;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::{from_slice, Addr, Env, MessageInfo, Storage, Timestamp, Uint128};
-    use cosmwasm_storage::ReadonlyPrefixedStorage;
 
     fn mock_env_height(signer: &str, height: u64, time: u64) -> (Env, MessageInfo) {
         let mut env = mock_env();
@@ -327,32 +700,26 @@ mod tests {
     }
 
     fn get_constants(storage: &dyn Storage) -> Constants {
-        let config_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
-        let data = config_storage
-            .get(KEY_CONSTANTS)
-            .expect("no config data stored");
-        from_slice(&data).expect("invalid data")
+        CONSTANTS.load(storage).expect("no config data stored")
     }
 
     fn get_total_supply(storage: &dyn Storage) -> u128 {
-        let config_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
-        let data = config_storage
-            .get(KEY_TOTAL_SUPPLY)
-            .expect("no decimals data stored");
-        return bytes_to_u128(&data).unwrap();
+        TOTAL_SUPPLY
+            .load(storage)
+            .expect("no total supply data stored")
+            .u128()
     }
 
     fn get_balance(storage: &dyn Storage, address: &Addr) -> u128 {
-        let balances_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_BALANCES);
-        return read_u128(&balances_storage, address).unwrap();
+        read_balance(storage, address).unwrap().u128()
     }
 
     fn get_allowance(storage: &dyn Storage, owner: &Addr, spender: &Addr) -> u128 {
-        let owner_storage = ReadonlyPrefixedStorage::multilevel(
-            storage,
-            &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()],
-        );
-        return read_u128(&owner_storage, spender).unwrap();
+        ALLOWANCES
+            .may_load(storage, (owner, spender))
+            .unwrap()
+            .unwrap_or_default()
+            .u128()
     }
 
     mod instantiate {
@@ -369,8 +736,10 @@ mod tests {
                 initial_balances: [InitialBalance {
                     address: "addr0000".to_string(),
                     amount: Uint128::from(11223344u128),
+                    vesting: None,
                 }]
                 .to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -398,6 +767,7 @@ mod tests {
                 symbol: "CASH".to_string(),
                 decimals: 9,
                 initial_balances: [].to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -416,17 +786,21 @@ mod tests {
                     InitialBalance {
                         address: "addr0000".to_string(),
                         amount: Uint128::from(11u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: "addr1111".to_string(),
                         amount: Uint128::from(22u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: "addrbbbb".to_string(),
                         amount: Uint128::from(33u128),
+                        vesting: None,
                     },
                 ]
                 .to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -460,8 +834,10 @@ mod tests {
                 initial_balances: [InitialBalance {
                     address: "addr0000".to_string(),
                     amount: Uint128::from(9007199254740993u128),
+                    vesting: None,
                 }]
                 .to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -484,8 +860,10 @@ mod tests {
                 initial_balances: [InitialBalance {
                     address: "addr0000".to_string(),
                     amount: Uint128::from(100000000000000000000000000u128),
+                    vesting: None,
                 }]
                 .to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -505,6 +883,7 @@ mod tests {
                 symbol: "CASH".to_string(),
                 decimals: 42,
                 initial_balances: [].to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -523,6 +902,7 @@ mod tests {
                 symbol: "CASH".to_string(),
                 decimals: 9,
                 initial_balances: [].to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -541,6 +921,7 @@ mod tests {
                 symbol: "CASH".to_string(),
                 decimals: 9,
                 initial_balances: [].to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -559,6 +940,7 @@ mod tests {
                 symbol: "DD".to_string(),
                 decimals: 9,
                 initial_balances: [].to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -577,6 +959,7 @@ mod tests {
                 symbol: "SUPERCOIN".to_string(),
                 decimals: 9,
                 initial_balances: [].to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -595,6 +978,7 @@ mod tests {
                 symbol: "CaSH".to_string(),
                 decimals: 9,
                 initial_balances: [].to_vec(),
+                marketing: None,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -620,16 +1004,20 @@ mod tests {
                     InitialBalance {
                         address: "addr0000".to_string(),
                         amount: Uint128::from(11u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: "addr1111".to_string(),
                         amount: Uint128::from(22u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: "addrbbbb".to_string(),
                         amount: Uint128::from(33u128),
+                        vesting: None,
                     },
                 ],
+                marketing: None,
             }
         }
 
@@ -879,6 +1267,140 @@ mod tests {
         }
     }
 
+    mod batch_transfer {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                        vesting: None,
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(22u128),
+                        vesting: None,
+                    },
+                ],
+                marketing: None,
+            }
+        }
+
+        #[test]
+        fn can_send_to_multiple_recipients() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Batch transfer
+            let batch_msg = ExecuteMsg::BatchTransfer {
+                transfers: vec![
+                    ("addr1111".to_string(), Uint128::from(3u128)),
+                    ("addr2323".to_string(), Uint128::from(5u128)),
+                ],
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let batch_result = execute(deps.as_mut(), env, info, batch_msg).unwrap();
+            assert_eq!(batch_result.messages.len(), 0);
+            assert_eq!(
+                batch_result.attributes,
+                vec![
+                    attr("action", "batch_transfer"),
+                    attr("sender", "addr0000"),
+                    attr("recipient", "addr1111"),
+                    attr("amount", "3"),
+                    attr("recipient", "addr2323"),
+                    attr("amount", "5"),
+                ]
+            );
+            // New state
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 3); // -8
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 25); // +3
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr2323")), 5); // +5
+            assert_eq!(get_total_supply(&deps.storage), 33);
+        }
+
+        #[test]
+        fn fails_on_insufficient_total_balance() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Each transfer fits the balance on its own, but not both together
+            let batch_msg = ExecuteMsg::BatchTransfer {
+                transfers: vec![
+                    ("addr1111".to_string(), Uint128::from(6u128)),
+                    ("addr2323".to_string(), Uint128::from(6u128)),
+                ],
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let batch_result = execute(deps.as_mut(), env, info, batch_msg);
+            match batch_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds {
+                    balance: 11,
+                    required: 12,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // New state (unchanged)
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 22);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr2323")), 0);
+        }
+
+        #[test]
+        fn fails_on_invalid_recipient() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let batch_msg = ExecuteMsg::BatchTransfer {
+                transfers: vec![
+                    ("addr1111".to_string(), Uint128::from(1u128)),
+                    ("".to_string(), Uint128::from(1u128)),
+                ],
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let batch_result = execute(deps.as_mut(), env, info, batch_msg);
+            match batch_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Std(_)) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // New state (unchanged)
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 22);
+        }
+
+        #[test]
+        fn fails_on_empty_batch() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let batch_msg = ExecuteMsg::BatchTransfer { transfers: vec![] };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let batch_result = execute(deps.as_mut(), env, info, batch_msg);
+            match batch_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::EmptyBatch {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
     mod approve {
         use super::*;
         use cosmwasm_std::attr;
@@ -892,16 +1414,20 @@ mod tests {
                     InitialBalance {
                         address: "addr0000".to_string(),
                         amount: Uint128::from(11u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: "addr1111".to_string(),
                         amount: Uint128::from(22u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: "addrbbbb".to_string(),
                         amount: Uint128::from(33u128),
+                        vesting: None,
                     },
                 ],
+                marketing: None,
             }
         }
 
@@ -1003,16 +1529,20 @@ mod tests {
                     InitialBalance {
                         address: "addr0000".to_string(),
                         amount: Uint128::from(11u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: "addr1111".to_string(),
                         amount: Uint128::from(22u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: "addrbbbb".to_string(),
                         amount: Uint128::from(33u128),
+                        vesting: None,
                     },
                 ],
+                marketing: None,
             }
         }
 
@@ -1194,12 +1724,15 @@ mod tests {
                     InitialBalance {
                         address: "addr0000".to_string(),
                         amount: Uint128::from(11u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: "addr1111".to_string(),
                         amount: Uint128::from(22u128),
+                        vesting: None,
                     },
                 ],
+                marketing: None,
             }
         }
 
@@ -1335,16 +1868,513 @@ mod tests {
         }
     }
 
-    mod query {
+    mod vesting {
         use super::*;
-        use cosmwasm_std::{attr, Addr};
+        use crate::error::ContractError;
+        use crate::state::VestingSchedule;
+        use cosmwasm_std::attr;
 
-        fn address(index: u8) -> Addr {
-            match index {
-                0 => Addr::unchecked("addr0000".to_string()), // contract instantiateializer
-                1 => Addr::unchecked("addr1111".to_string()),
-                2 => Addr::unchecked("addr4321".to_string()),
-                3 => Addr::unchecked("addr5432".to_string()),
+        fn make_schedule() -> VestingSchedule {
+            VestingSchedule {
+                start: 1000,
+                cliff: 1500,
+                end: 2000,
+                total: Uint128::from(80u128),
+            }
+        }
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(100u128),
+                        vesting: Some(make_schedule()),
+                    },
+                    InitialBalance {
+                        address: "creator".to_string(),
+                        amount: Uint128::from(50u128),
+                        vesting: None,
+                    },
+                ],
+                marketing: None,
+            }
+        }
+
+        #[test]
+        fn fails_for_schedule_exceeding_balance() {
+            let mut deps = mock_dependencies(&[]);
+            let mut instantiate_msg = make_instantiate_msg();
+            instantiate_msg.initial_balances[0].amount = Uint128::from(79u128);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidVestingSchedule {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn can_only_transfer_unlocked_amount() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Before the cliff everything is locked
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(21u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 1499);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg);
+            match transfer_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientUnlockedFunds {
+                    unlocked: 20,
+                    required: 21,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // Halfway between cliff and end 60 of 80 are vested
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(80u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 1750);
+            execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 20);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 80);
+            // Burning locked funds fails as well
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(1u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 1750);
+            let burn_result = execute(deps.as_mut(), env, info, burn_msg);
+            match burn_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientUnlockedFunds {
+                    unlocked: 0,
+                    required: 1,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // After the end everything is unlocked
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(20u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 2000);
+            execute(deps.as_mut(), env, info, burn_msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 0);
+        }
+
+        #[test]
+        fn admin_can_create_schedule() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let create_msg = ExecuteMsg::CreateVestingSchedule {
+                address: "addr2222".to_string(),
+                schedule: VestingSchedule {
+                    start: 1000,
+                    cliff: 1000,
+                    end: 3000,
+                    total: Uint128::from(40u128),
+                },
+            };
+            // Only the admin may create schedules
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let create_result = execute(deps.as_mut(), env, info, create_msg.clone());
+            match create_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let create_result = execute(deps.as_mut(), env, info, create_msg.clone()).unwrap();
+            assert_eq!(
+                create_result.attributes,
+                vec![
+                    attr("action", "create_vesting_schedule"),
+                    attr("admin", "creator"),
+                    attr("address", "addr2222"),
+                    attr("amount", "40"),
+                ]
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("creator")), 10);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr2222")), 40);
+            // A second schedule cannot replace one with locked funds
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let create_result = execute(deps.as_mut(), env, info, create_msg);
+            match create_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::VestingScheduleExists {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn can_query_vesting_schedule() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let (env, _) = mock_env_height("addr0000", 450, 1750);
+            let query_msg = QueryMsg::VestingSchedule {
+                address: "addr0000".to_string(),
+            };
+            let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+            let response: VestingScheduleResponse = from_slice(&query_result).unwrap();
+            assert_eq!(response.schedule, Some(make_schedule()));
+            assert_eq!(response.locked, Uint128::from(20u128));
+            assert_eq!(response.unlocked, Uint128::from(60u128));
+            // Accounts without a schedule have nothing locked
+            let query_msg = QueryMsg::VestingSchedule {
+                address: "creator".to_string(),
+            };
+            let query_result = query(deps.as_ref(), env, query_msg).unwrap();
+            assert_eq!(
+                query_result.as_slice(),
+                b"{\"schedule\":null,\"locked\":\"0\",\"unlocked\":\"0\"}"
+            );
+        }
+    }
+
+    mod marketing {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::InstantiateMarketingInfo;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![],
+                marketing: Some(InstantiateMarketingInfo {
+                    project: Some("https://cash.example".to_string()),
+                    description: Some("The cash token".to_string()),
+                    marketing: Some("marketing".to_string()),
+                    logo: Some(Logo::Url("https://cash.example/logo.png".to_string())),
+                }),
+            }
+        }
+
+        fn get_marketing_info(deps: Deps) -> MarketingInfoResponse {
+            let query_result = query(deps, mock_env(), QueryMsg::MarketingInfo {}).unwrap();
+            from_slice(&query_result).unwrap()
+        }
+
+        #[test]
+        fn instantiate_stores_marketing_info() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(
+                get_marketing_info(deps.as_ref()),
+                MarketingInfoResponse {
+                    project: Some("https://cash.example".to_string()),
+                    description: Some("The cash token".to_string()),
+                    logo: Some(LogoInfo::Url("https://cash.example/logo.png".to_string())),
+                    marketing: Some(Addr::unchecked("marketing")),
+                }
+            );
+            // URL logos cannot be downloaded
+            let download_result = query(deps.as_ref(), mock_env(), QueryMsg::DownloadLogo {});
+            match download_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Std(StdError::NotFound { .. })) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn can_update_marketing() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let update_msg = ExecuteMsg::UpdateMarketing {
+                project: None,
+                description: Some("".to_string()),
+                marketing: Some("marketing2".to_string()),
+            };
+            // Only the marketing admin may update
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let update_result = execute(deps.as_mut(), env, info, update_msg.clone());
+            match update_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            let (env, info) = mock_env_height("marketing", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+            assert_eq!(
+                get_marketing_info(deps.as_ref()),
+                MarketingInfoResponse {
+                    project: Some("https://cash.example".to_string()),
+                    description: None,
+                    logo: Some(LogoInfo::Url("https://cash.example/logo.png".to_string())),
+                    marketing: Some(Addr::unchecked("marketing2")),
+                }
+            );
+        }
+
+        #[test]
+        fn can_upload_and_download_logo() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let svg = Binary::from(&b"<?xml version=\"1.0\"?><svg></svg>"[..]);
+            let upload_msg = ExecuteMsg::UploadLogo(Logo::Embedded(EmbeddedLogo::Svg(svg.clone())));
+            let (env, info) = mock_env_height("marketing", 450, 550);
+            execute(deps.as_mut(), env, info, upload_msg).unwrap();
+            assert_eq!(
+                get_marketing_info(deps.as_ref()).logo,
+                Some(LogoInfo::Embedded)
+            );
+            let query_result = query(deps.as_ref(), mock_env(), QueryMsg::DownloadLogo {}).unwrap();
+            let logo: DownloadLogoResponse = from_slice(&query_result).unwrap();
+            assert_eq!(
+                logo,
+                DownloadLogoResponse {
+                    mime_type: "image/svg+xml".to_string(),
+                    data: svg,
+                }
+            );
+        }
+
+        #[test]
+        fn fails_for_invalid_logo() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            // SVG without preamble
+            let upload_msg = ExecuteMsg::UploadLogo(Logo::Embedded(EmbeddedLogo::Svg(
+                Binary::from(&b"<svg></svg>"[..]),
+            )));
+            let (env, info) = mock_env_height("marketing", 450, 550);
+            match execute(deps.as_mut(), env, info, upload_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidXmlPreamble {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // PNG without signature
+            let upload_msg = ExecuteMsg::UploadLogo(Logo::Embedded(EmbeddedLogo::Png(
+                Binary::from(&b"not a png"[..]),
+            )));
+            let (env, info) = mock_env_height("marketing", 450, 550);
+            match execute(deps.as_mut(), env, info, upload_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidPngHeader {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // PNG above the size cap
+            let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+            png.resize(LOGO_SIZE_CAP + 1, 0);
+            let upload_msg =
+                ExecuteMsg::UploadLogo(Logo::Embedded(EmbeddedLogo::Png(Binary::from(png))));
+            let (env, info) = mock_env_height("marketing", 450, 550);
+            match execute(deps.as_mut(), env, info, upload_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::LogoTooBig {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod transfer_fee {
+        use super::*;
+        use crate::error::ContractError;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(1000u128),
+                        vesting: None,
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(1000u128),
+                        vesting: None,
+                    },
+                ],
+                marketing: None,
+            }
+        }
+
+        fn set_fee(deps: DepsMut) {
+            let fee_msg = ExecuteMsg::UpdateFeeConfig {
+                fee: Some(TransferFee {
+                    basis_points: 250,
+                    collector: "collector".to_string(),
+                    exempt: vec!["addr1111".to_string()],
+                }),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps, env, info, fee_msg).unwrap();
+        }
+
+        #[test]
+        fn only_admin_can_set_fee() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let fee_msg = ExecuteMsg::UpdateFeeConfig {
+                fee: Some(TransferFee {
+                    basis_points: 10_001,
+                    collector: "collector".to_string(),
+                    exempt: vec![],
+                }),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, fee_msg.clone()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            let (env, info) = mock_env_height("creator", 450, 550);
+            match execute(deps.as_mut(), env, info, fee_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::FeeTooHigh {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn transfer_splits_fee() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            set_fee(deps.as_mut());
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr2222".to_string(),
+                amount: Uint128::from(400u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                600
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr2222")),
+                390
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("collector")),
+                10
+            );
+            assert_eq!(get_total_supply(&deps.storage), 2000);
+            // Exempt senders pay no fee
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr2222".to_string(),
+                amount: Uint128::from(400u128),
+            };
+            let (env, info) = mock_env_height("addr1111", 450, 550);
+            execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr2222")),
+                790
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("collector")),
+                10
+            );
+        }
+
+        #[test]
+        fn transfer_from_charges_gross_allowance() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            set_fee(deps.as_mut());
+            let approve_msg = ExecuteMsg::Approve {
+                spender: "spender".to_string(),
+                amount: Uint128::from(500u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            let transfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: "addr0000".to_string(),
+                recipient: "addr2222".to_string(),
+                amount: Uint128::from(200u128),
+            };
+            let (env, info) = mock_env_height("spender", 450, 550);
+            execute(deps.as_mut(), env, info, transfer_from_msg).unwrap();
+            assert_eq!(
+                get_allowance(
+                    &deps.storage,
+                    &Addr::unchecked("addr0000"),
+                    &Addr::unchecked("spender")
+                ),
+                300
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                800
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr2222")),
+                195
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("collector")), 5);
+        }
+
+        #[test]
+        fn can_preview_net_amount() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let query_msg = QueryMsg::TransferFee {
+                sender: "addr0000".to_string(),
+                recipient: "addr2222".to_string(),
+                amount: Uint128::from(400u128),
+            };
+            // No fee configured
+            let query_result = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+            assert_eq!(
+                query_result.as_slice(),
+                b"{\"fee\":\"0\",\"net_amount\":\"400\"}"
+            );
+            set_fee(deps.as_mut());
+            let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            assert_eq!(
+                query_result.as_slice(),
+                b"{\"fee\":\"10\",\"net_amount\":\"390\"}"
+            );
+        }
+    }
+
+    mod query {
+        use super::*;
+        use cosmwasm_std::{attr, Addr};
+
+        fn address(index: u8) -> Addr {
+            match index {
+                0 => Addr::unchecked("addr0000".to_string()), // contract instantiateializer
+                1 => Addr::unchecked("addr1111".to_string()),
+                2 => Addr::unchecked("addr4321".to_string()),
+                3 => Addr::unchecked("addr5432".to_string()),
                 4 => Addr::unchecked("addr6543".to_string()),
                 _ => panic!("Unsupported address index"),
             }
@@ -1359,16 +2389,20 @@ mod tests {
                     InitialBalance {
                         address: address(1).to_string(),
                         amount: Uint128::from(11u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: address(2).to_string(),
                         amount: Uint128::from(22u128),
+                        vesting: None,
                     },
                     InitialBalance {
                         address: address(3).to_string(),
                         amount: Uint128::from(33u128),
+                        vesting: None,
                     },
                 ],
+                marketing: None,
             }
         }
 
@@ -1473,4 +2507,129 @@ mod tests {
             assert_eq!(query_result.as_slice(), b"{\"allowance\":\"0\"}");
         }
     }
+
+    mod migrate {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::legacy::{
+            KEY_CONSTANTS, KEY_TOTAL_SUPPLY, PREFIX_ALLOWANCES, PREFIX_BALANCES, PREFIX_CONFIG,
+        };
+        use cosmwasm_std::to_vec;
+        use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+        use cw2::get_contract_version;
+
+        // Writes the storage layout of a contract instantiated before cw-storage-plus
+        fn write_legacy_state(storage: &mut dyn Storage) {
+            let mut config_store = PrefixedStorage::new(storage, PREFIX_CONFIG);
+            let constants = to_vec(&Constants {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+            })
+            .unwrap();
+            config_store.set(KEY_CONSTANTS, &constants);
+            config_store.set(KEY_TOTAL_SUPPLY, &33u128.to_be_bytes());
+            let mut balances_store = PrefixedStorage::new(storage, PREFIX_BALANCES);
+            balances_store.set(b"addr0000", &11u128.to_be_bytes());
+            balances_store.set(b"addr1111", &22u128.to_be_bytes());
+            let mut allowances_store =
+                PrefixedStorage::multilevel(storage, &[PREFIX_ALLOWANCES, b"addr0000"]);
+            allowances_store.set(b"spender", &7u128.to_be_bytes());
+        }
+
+        #[test]
+        fn migrates_legacy_storage() {
+            let mut deps = mock_dependencies(&[]);
+            write_legacy_state(&mut deps.storage);
+            let msg = MigrateMsg {
+                admin: Some("creator".to_string()),
+            };
+            let res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_constants(&deps.storage),
+                Constants {
+                    name: "Cash Token".to_string(),
+                    symbol: "CASH".to_string(),
+                    decimals: 9
+                }
+            );
+            assert_eq!(get_total_supply(&deps.storage), 33);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 22);
+            assert_eq!(
+                get_allowance(
+                    &deps.storage,
+                    &Addr::unchecked("addr0000"),
+                    &Addr::unchecked("spender")
+                ),
+                7
+            );
+            // Legacy entries are gone
+            let balances_store = ReadonlyPrefixedStorage::new(&deps.storage, PREFIX_BALANCES);
+            assert_eq!(balances_store.get(b"addr0000"), None);
+            let config_store = ReadonlyPrefixedStorage::new(&deps.storage, PREFIX_CONFIG);
+            assert_eq!(config_store.get(KEY_TOTAL_SUPPLY), None);
+            assert_eq!(ADMIN.load(&deps.storage).unwrap(), "creator");
+            // The contract version is recorded
+            let version = get_contract_version(&deps.storage).unwrap();
+            assert_eq!(version.contract, CONTRACT_NAME);
+            assert_eq!(version.version, CONTRACT_VERSION);
+        }
+
+        #[test]
+        fn fails_on_corrupted_legacy_balance() {
+            let mut deps = mock_dependencies(&[]);
+            write_legacy_state(&mut deps.storage);
+            let mut balances_store = PrefixedStorage::new(&mut deps.storage, PREFIX_BALANCES);
+            balances_store.set(b"addr2222", &[1, 2, 3]);
+            let msg = MigrateMsg {
+                admin: Some("creator".to_string()),
+            };
+            match migrate(deps.as_mut(), mock_env(), msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::CorruptedDataFound {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_on_legacy_storage_without_admin() {
+            let mut deps = mock_dependencies(&[]);
+            write_legacy_state(&mut deps.storage);
+            match migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MissingAdmin {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn refuses_downgrade() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![],
+                marketing: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            // Migrating to the same version is fine
+            migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }).unwrap();
+            set_contract_version(&mut deps.storage, CONTRACT_NAME, "999.0.0").unwrap();
+            match migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::CannotDowngrade { stored, .. }) => assert_eq!(stored, "999.0.0"),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            set_contract_version(&mut deps.storage, "crates.io:other", "0.1.0").unwrap();
+            match migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::WrongContract { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
 }
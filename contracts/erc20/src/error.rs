@@ -21,6 +21,51 @@ pub enum ContractError {
     #[error("Insufficient funds (balance {balance}, required={required})")]
     InsufficientFunds { balance: u128, required: u128 },
 
+    #[error("Insufficient unlocked funds (unlocked {unlocked}, required={required})")]
+    InsufficientUnlockedFunds { unlocked: u128, required: u128 },
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Vesting schedule must satisfy start <= cliff <= end and not exceed the balance")]
+    InvalidVestingSchedule {},
+
+    #[error("Address already has a vesting schedule with locked funds")]
+    VestingScheduleExists {},
+
+    #[error("Logo binary data exceeds 5KB limit")]
+    LogoTooBig {},
+
+    #[error("Invalid xml preamble for SVG")]
+    InvalidXmlPreamble {},
+
+    #[error("Invalid png header")]
+    InvalidPngHeader {},
+
+    #[error("Transfer fee must not exceed 10000 basis points")]
+    FeeTooHigh {},
+
+    #[error("Batch transfer must contain at least one transfer")]
+    EmptyBatch {},
+
     #[error("Corrupted data found (16 byte expected)")]
     CorruptedDataFound {},
+
+    #[error("Cannot migrate from a different contract ({found}, expected {expected})")]
+    WrongContract { expected: String, found: String },
+
+    #[error("Migrating a contract without stored admin requires an admin")]
+    MissingAdmin {},
+
+    #[error("Cannot migrate from newer version {stored} to {new}")]
+    CannotDowngrade { stored: String, new: String },
+
+    #[error("Invalid version: {0}")]
+    SemVer(String),
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
 }
@@ -0,0 +1,106 @@
+//! Storage layout of contracts instantiated before the switch to cw-storage-plus.
+//! It is only read by `migrate` to move the data into the typed storage of `state.rs`.
+
+use cosmwasm_std::{from_slice, Addr, Order, StdError, StdResult, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use serde::de::DeserializeOwned;
+use std::convert::TryInto;
+
+use crate::error::ContractError;
+use crate::state::{Constants, ALLOWANCES, BALANCES, CONSTANTS, TOTAL_SUPPLY};
+
+pub const PREFIX_CONFIG: &[u8] = b"config";
+pub const PREFIX_BALANCES: &[u8] = b"balances";
+pub const PREFIX_ALLOWANCES: &[u8] = b"allowances";
+
+pub const KEY_CONSTANTS: &[u8] = b"constants";
+pub const KEY_TOTAL_SUPPLY: &[u8] = b"total_supply";
+
+/// Moves all legacy data into typed storage and removes the legacy entries
+pub fn migrate_legacy_storage(store: &mut dyn Storage) -> Result<(), ContractError> {
+    migrate_config(store)?;
+    migrate_balances(store)?;
+    migrate_allowances(store)?;
+    Ok(())
+}
+
+fn migrate_config(store: &mut dyn Storage) -> Result<(), ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    let constants: Constants =
+        read_json(&config_store, KEY_CONSTANTS)?.ok_or_else(|| StdError::not_found("constants"))?;
+    let total_supply = match config_store.get(KEY_TOTAL_SUPPLY) {
+        Some(data) => bytes_to_u128(&data)?,
+        None => return Err(StdError::not_found("total supply").into()),
+    };
+
+    CONSTANTS.save(store, &constants)?;
+    TOTAL_SUPPLY.save(store, &Uint128::from(total_supply))?;
+
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.remove(KEY_CONSTANTS);
+    config_store.remove(KEY_TOTAL_SUPPLY);
+    Ok(())
+}
+
+fn migrate_balances(store: &mut dyn Storage) -> Result<(), ContractError> {
+    let balances: Vec<_> = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES)
+        .range(None, None, Order::Ascending)
+        .collect();
+    for (key, value) in balances {
+        let owner = addr_from_key(&key)?;
+        BALANCES.save(store, &owner, &Uint128::from(bytes_to_u128(&value)?))?;
+        PrefixedStorage::new(store, PREFIX_BALANCES).remove(&key);
+    }
+    Ok(())
+}
+
+// Allowances were stored in one namespace per owner, nested below PREFIX_ALLOWANCES.
+// Ranging over PREFIX_ALLOWANCES yields keys of the form
+// `owner length (2 bytes, big endian) | owner | spender`.
+fn migrate_allowances(store: &mut dyn Storage) -> Result<(), ContractError> {
+    let allowances: Vec<_> = ReadonlyPrefixedStorage::new(store, PREFIX_ALLOWANCES)
+        .range(None, None, Order::Ascending)
+        .collect();
+    for (key, value) in allowances {
+        if key.len() < 2 {
+            return Err(StdError::generic_err("Corrupted allowance key").into());
+        }
+        let owner_len = u16::from_be_bytes([key[0], key[1]]) as usize;
+        if key.len() < 2 + owner_len {
+            return Err(StdError::generic_err("Corrupted allowance key").into());
+        }
+        let owner = addr_from_key(&key[2..2 + owner_len])?;
+        let spender = addr_from_key(&key[2 + owner_len..])?;
+        ALLOWANCES.save(
+            store,
+            (&owner, &spender),
+            &Uint128::from(bytes_to_u128(&value)?),
+        )?;
+        PrefixedStorage::new(store, PREFIX_ALLOWANCES).remove(&key);
+    }
+    Ok(())
+}
+
+fn read_json<T: DeserializeOwned>(
+    store: &ReadonlyPrefixedStorage,
+    key: &[u8],
+) -> StdResult<Option<T>> {
+    match store.get(key) {
+        Some(data) => from_slice(&data).map(Some),
+        None => Ok(None),
+    }
+}
+
+// Legacy keys are the plain address bytes, which were never validated on instantiate
+fn addr_from_key(key: &[u8]) -> StdResult<Addr> {
+    Ok(Addr::unchecked(String::from_utf8(key.to_vec())?))
+}
+
+// Converts 16 bytes value into u128
+// Errors if data found that is not 16 bytes
+pub fn bytes_to_u128(data: &[u8]) -> Result<u128, ContractError> {
+    match data.try_into() {
+        Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
+        Err(_) => Err(ContractError::CorruptedDataFound {}),
+    }
+}
@@ -1,9 +1,13 @@
 pub mod contract;
 mod error;
+mod legacy;
 mod msg;
 mod state;
 
 pub use msg::{
-    AllowanceResponse, BalanceResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg,
+    AllowanceResponse, BalanceResponse, DownloadLogoResponse, EmbeddedLogo, ExecuteMsg,
+    FeeConfigResponse, InitialBalance, InstantiateMarketingInfo, InstantiateMsg, Logo,
+    MarketingInfoResponse, MigrateMsg, QueryMsg, TransferFee, TransferFeeResponse,
+    VestingScheduleResponse,
 };
-pub use state::Constants;
+pub use state::{Constants, FeeConfig, LogoInfo, VestingSchedule};
@@ -1,12 +1,17 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Addr, Binary, Uint128};
+
+use crate::state::{FeeConfig, LogoInfo, VestingSchedule};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct InitialBalance {
     pub address: String,
     pub amount: Uint128,
+    /// Optionally locks up to `amount` of this balance under a vesting schedule
+    #[serde(default)]
+    pub vesting: Option<VestingSchedule>,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema)]
@@ -15,9 +20,45 @@ pub struct InstantiateMsg {
     pub symbol: String,
     pub decimals: u8,
     pub initial_balances: Vec<InitialBalance>,
+    #[serde(default)]
+    pub marketing: Option<InstantiateMarketingInfo>,
 }
 
-#[derive(Serialize, Deserialize, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferFee {
+    /// The fee in 1/10000 of the transferred amount, at most 10000
+    pub basis_points: u16,
+    pub collector: String,
+    pub exempt: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMarketingInfo {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    /// The address allowed to update marketing info and the logo
+    pub marketing: Option<String>,
+    pub logo: Option<Logo>,
+}
+
+/// A logo is either a link to an external image or an image stored on chain
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Logo {
+    Url(String),
+    Embedded(EmbeddedLogo),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddedLogo {
+    /// Must be an XML document starting with an `<?xml ... ?>` preamble
+    Svg(Binary),
+    /// Must start with the PNG file signature
+    Png(Binary),
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Approve {
@@ -28,6 +69,9 @@ pub enum ExecuteMsg {
         recipient: String,
         amount: Uint128,
     },
+    BatchTransfer {
+        transfers: Vec<(String, Uint128)>,
+    },
     TransferFrom {
         owner: String,
         recipient: String,
@@ -36,13 +80,57 @@ pub enum ExecuteMsg {
     Burn {
         amount: Uint128,
     },
+    /// Admin only. Sends `schedule.total` from the admin to `address` and locks it
+    /// under the given schedule.
+    CreateVestingSchedule {
+        address: String,
+        schedule: VestingSchedule,
+    },
+    /// Marketing admin only. `None` leaves a field unchanged, an empty string clears it.
+    UpdateMarketing {
+        project: Option<String>,
+        description: Option<String>,
+        marketing: Option<String>,
+    },
+    /// Marketing admin only
+    UploadLogo(Logo),
+    /// Admin only. Sets the transfer fee, `None` disables it.
+    UpdateFeeConfig {
+        fee: Option<TransferFee>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    /// Replaces the admin of vesting schedules and transfer fees. Required when
+    /// migrating a contract instantiated before cw2, which did not store an admin.
+    #[serde(default)]
+    pub admin: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    Balance { address: String },
-    Allowance { owner: String, spender: String },
+    Balance {
+        address: String,
+    },
+    Allowance {
+        owner: String,
+        spender: String,
+    },
+    VestingSchedule {
+        address: String,
+    },
+    MarketingInfo {},
+    /// Returns the embedded logo, fails if the logo is a URL or not set
+    DownloadLogo {},
+    FeeConfig {},
+    /// Previews the fee taken from a transfer and the amount reaching the recipient
+    TransferFee {
+        sender: String,
+        recipient: String,
+        amount: Uint128,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -54,3 +142,35 @@ pub struct BalanceResponse {
 pub struct AllowanceResponse {
     pub allowance: Uint128,
 }
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct VestingScheduleResponse {
+    pub schedule: Option<VestingSchedule>,
+    pub locked: Uint128,
+    pub unlocked: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketingInfoResponse {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub logo: Option<LogoInfo>,
+    pub marketing: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DownloadLogoResponse {
+    pub mime_type: String,
+    pub data: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeConfigResponse {
+    pub fee_config: Option<FeeConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferFeeResponse {
+    pub fee: Uint128,
+    pub net_amount: Uint128,
+}
@@ -1,9 +1,99 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use cosmwasm_std::{Addr, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map};
+
+use crate::msg::Logo;
+
+pub const CONSTANTS: Item<Constants> = Item::new("constants");
+pub const TOTAL_SUPPLY: Item<Uint128> = Item::new("total_supply");
+pub const ADMIN: Item<Addr> = Item::new("admin");
+pub const MARKETING_INFO: Item<MarketingInfo> = Item::new("marketing_info");
+pub const LOGO: Item<Logo> = Item::new("logo");
+pub const FEE_CONFIG: Item<FeeConfig> = Item::new("fee_config");
+
+pub const BALANCES: Map<&Addr, Uint128> = Map::new("balance");
+/// Keyed by (owner, spender)
+pub const ALLOWANCES: Map<(&Addr, &Addr), Uint128> = Map::new("allowance");
+pub const VESTING: Map<&Addr, VestingSchedule> = Map::new("vesting_schedule");
+
 #[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct Constants {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
 }
+
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct MarketingInfo {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub logo: Option<LogoInfo>,
+    pub marketing: Option<Addr>,
+}
+
+/// Tells where the logo can be found, embedded logos are downloaded separately
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogoInfo {
+    Url(String),
+    Embedded,
+}
+
+/// An optional fee taken from every transfer and credited to `collector`
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct FeeConfig {
+    /// The fee in 1/10000 of the transferred amount
+    pub basis_points: u16,
+    pub collector: Addr,
+    /// Transfers from or to any of these addresses are free
+    pub exempt: Vec<Addr>,
+}
+
+impl FeeConfig {
+    /// Returns the fee charged for sending `amount` from `from` to `to`
+    pub fn fee_for(&self, from: &Addr, to: &Addr, amount: Uint128) -> Uint128 {
+        if self.exempt.contains(from) || self.exempt.contains(to) {
+            return Uint128::zero();
+        }
+        amount.multiply_ratio(self.basis_points, 10_000u128)
+    }
+}
+
+/// A linear vesting schedule locking part of an account balance.
+///
+/// All times are block times in seconds. Nothing is vested before `cliff`,
+/// everything is vested from `end` on, and in between tokens unlock linearly
+/// from `start`.
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct VestingSchedule {
+    pub start: u64,
+    pub cliff: u64,
+    pub end: u64,
+    pub total: Uint128,
+}
+
+impl VestingSchedule {
+    pub fn is_valid(&self) -> bool {
+        self.start <= self.cliff && self.cliff <= self.end
+    }
+
+    /// Returns the amount of `total` which is unlocked at the given block time
+    pub fn vested_amount(&self, time: Timestamp) -> Uint128 {
+        let now = time.seconds();
+        if now < self.cliff {
+            Uint128::zero()
+        } else if now >= self.end {
+            self.total
+        } else {
+            self.total
+                .multiply_ratio(now - self.start, self.end - self.start)
+        }
+    }
+
+    /// Returns the amount of `total` which is still locked at the given block time
+    pub fn locked_amount(&self, time: Timestamp) -> Uint128 {
+        self.total - self.vested_amount(time)
+    }
+}
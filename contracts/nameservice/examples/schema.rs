@@ -1,8 +1,13 @@
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
 use std::env::current_dir;
 use std::fs::create_dir_all;
 
-use cw_nameservice::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ResolveRecordResponse};
+use cw721::{NftInfoResponse, OwnerOfResponse, TokensResponse};
+use cw_nameservice::msg::{
+    AuctionResponse, ExecuteMsg, InstantiateMsg, ListingsResponse, NameMetadata, NamesResponse,
+    OperatorsResponse, PriceResponse, QueryMsg, RecordsResponse, ReservedResponse,
+    ResolveRecordResponse, ReverseResolveResponse, TransferOfferResponse, TransferOffersResponse,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -14,4 +19,21 @@ fn main() {
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(ResolveRecordResponse), &out_dir);
+    export_schema(&schema_for!(RecordsResponse), &out_dir);
+    export_schema(&schema_for!(ReverseResolveResponse), &out_dir);
+    export_schema(&schema_for!(PriceResponse), &out_dir);
+    export_schema(&schema_for!(AuctionResponse), &out_dir);
+    export_schema(&schema_for!(ListingsResponse), &out_dir);
+    export_schema(&schema_for!(NamesResponse), &out_dir);
+    export_schema(&schema_for!(OwnerOfResponse), &out_dir);
+    export_schema_with_title(
+        &schema_for!(NftInfoResponse<NameMetadata>),
+        &out_dir,
+        "NftInfoResponse",
+    );
+    export_schema(&schema_for!(TokensResponse), &out_dir);
+    export_schema(&schema_for!(ReservedResponse), &out_dir);
+    export_schema(&schema_for!(TransferOfferResponse), &out_dir);
+    export_schema(&schema_for!(TransferOffersResponse), &out_dir);
+    export_schema(&schema_for!(OperatorsResponse), &out_dir);
 }
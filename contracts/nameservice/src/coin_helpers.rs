@@ -24,6 +24,34 @@ pub fn assert_sent_sufficient_coin(
     Ok(())
 }
 
+/// Returns everything sent on top of the required coin, including unrelated denoms
+pub fn excess_funds(sent: &[Coin], required: Option<&Coin>) -> Vec<Coin> {
+    let mut charged = false;
+    sent.iter()
+        .filter_map(|coin| {
+            let mut amount = coin.amount;
+            if let Some(required_coin) = required {
+                // the price is taken from a single coin, as checked above
+                if !charged
+                    && coin.denom == required_coin.denom
+                    && coin.amount >= required_coin.amount
+                {
+                    amount = coin.amount - required_coin.amount;
+                    charged = true;
+                }
+            }
+            if amount.is_zero() {
+                None
+            } else {
+                Some(Coin {
+                    denom: coin.denom.clone(),
+                    amount,
+                })
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -59,4 +87,24 @@ mod test {
             Err(e) => panic!("Unexpected error: {:?}", e),
         };
     }
+
+    #[test]
+    fn excess_funds_works() {
+        assert_eq!(excess_funds(&[], None), vec![]);
+        assert_eq!(excess_funds(&coins(5, "token"), None), coins(5, "token"));
+        assert_eq!(
+            excess_funds(&coins(5, "token"), Some(&coin(5, "token"))),
+            vec![]
+        );
+        assert_eq!(
+            excess_funds(&coins(8, "token"), Some(&coin(5, "token"))),
+            coins(3, "token")
+        );
+
+        let sent_coins = vec![coin(2, "smokin"), coin(7, "token"), coin(1, "earth")];
+        assert_eq!(
+            excess_funds(&sent_coins, Some(&coin(5, "token"))),
+            vec![coin(2, "smokin"), coin(2, "token"), coin(1, "earth")]
+        );
+    }
 }
@@ -1,26 +1,73 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    coin, entry_point, to_binary, Addr, BankMsg, Binary, BlockInfo, Coin, Deps, DepsMut, Empty,
+    Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128,
 };
 
-use crate::coin_helpers::assert_sent_sufficient_coin;
-use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ResolveRecordResponse};
-use crate::state::{config, config_read, resolver, resolver_read, Config, NameRecord};
+use cw721::{
+    Approval as Cw721Approval, Expiration, NftInfoResponse, OwnerOfResponse, TokensResponse,
+};
+
+use crate::coin_helpers::{assert_sent_sufficient_coin, excess_funds};
+use crate::error::{ContractError, InvalidCharacterReason};
+use crate::idn;
+use crate::msg::{
+    AuctionResponse, ExecuteMsg, InstantiateMsg, ListingInfo, ListingsResponse, NameMetadata,
+    NamesResponse, OperatorInfo, OperatorsResponse, PriceResponse, PriceUpdate, QueryMsg, Record,
+    RecordKey, RecordsResponse, ReservedPattern, ReservedResponse, ResolveRecordResponse,
+    ReverseResolveResponse, TransferOfferInfo, TransferOfferResponse, TransferOffersResponse,
+};
+use crate::state::{
+    auctions, auctions_read, config, config_read, escrow, escrow_read, listings, listings_read,
+    owner_names, owner_names_read, owner_operators, owner_operators_read, records, records_read,
+    reserved_names, reserved_names_read, reserved_prefixes, reserved_prefixes_read, resolver,
+    resolver_read, reverse, reverse_read, transfer_offers, transfer_offers_read, Approval, Auction,
+    Config, LengthPrice, Listing, NameRecord, Operator, Reservation, TransferOffer,
+};
 
 const MIN_NAME_LENGTH: u64 = 3;
 const MAX_NAME_LENGTH: u64 = 64;
+const MAX_FULL_NAME_LENGTH: u64 = 253;
+const MAX_URL_LENGTH: u64 = 256;
+const MAX_AVATAR_LENGTH: u64 = 256;
+const MAX_EMAIL_LENGTH: u64 = 128;
+const MAX_CONTENT_HASH_LENGTH: u64 = 128;
+const MAX_SALE_FEE_BPS: u64 = 10_000;
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, StdError> {
+    if msg.sale_fee_bps > MAX_SALE_FEE_BPS {
+        return Err(StdError::generic_err(
+            "sale_fee_bps must not exceed 10000 basis points",
+        ));
+    }
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
     let config_state = Config {
         purchase_price: msg.purchase_price,
         transfer_price: msg.transfer_price,
+        registration_period: msg.registration_period,
+        renewal_price: msg.renewal_price,
+        grace_period: msg.grace_period,
+        admin: Some(admin),
+        pending_admin: None,
+        strict_payment: msg.strict_payment,
+        length_prices: msg.length_prices,
+        auction: msg.auction,
+        sale_fee_bps: msg.sale_fee_bps,
+        unicode_names: msg.unicode_names,
     };
+    assert_auction_denom(&config_state)?;
 
     config(deps.storage).save(&config_state)?;
 
@@ -37,59 +84,1277 @@ pub fn execute(
     match msg {
         ExecuteMsg::Register { name } => execute_register(deps, env, info, name),
         ExecuteMsg::Transfer { name, to } => execute_transfer(deps, env, info, name, to),
+        ExecuteMsg::Renew { name } => execute_renew(deps, env, info, name),
+        ExecuteMsg::SetRecord { name, record } => execute_set_record(deps, env, info, name, record),
+        ExecuteMsg::RemoveRecord { name, key } => execute_remove_record(deps, env, info, name, key),
+        ExecuteMsg::SetPrimaryName { name } => execute_set_primary_name(deps, env, info, name),
+        ExecuteMsg::RemovePrimaryName {} => execute_remove_primary_name(deps, info),
+        ExecuteMsg::CreateSubdomain {
+            parent,
+            label,
+            owner,
+            reclaimable,
+        } => execute_create_subdomain(deps, env, info, parent, label, owner, reclaimable),
+        ExecuteMsg::ReclaimSubdomain { name } => execute_reclaim_subdomain(deps, env, info, name),
+        ExecuteMsg::Withdraw { amount, to } => execute_withdraw(deps, env, info, amount, to),
+        ExecuteMsg::UpdateConfig {
+            purchase_price,
+            transfer_price,
+            renewal_price,
+            length_prices,
+        } => execute_update_config(
+            deps,
+            info,
+            purchase_price,
+            transfer_price,
+            renewal_price,
+            length_prices,
+        ),
+        ExecuteMsg::TransferAdmin { admin } => execute_transfer_admin(deps, info, admin),
+        ExecuteMsg::AcceptAdmin {} => execute_accept_admin(deps, info),
+        ExecuteMsg::RenounceAdmin {} => execute_renounce_admin(deps, info),
+        ExecuteMsg::Bid { name } => execute_bid(deps, env, info, name),
+        ExecuteMsg::ClaimAuction { name } => execute_claim_auction(deps, env, name),
+        ExecuteMsg::ListForSale { name, price } => {
+            execute_list_for_sale(deps, env, info, name, price)
+        }
+        ExecuteMsg::CancelListing { name } => execute_cancel_listing(deps, info, name),
+        ExecuteMsg::Buy { name } => execute_buy(deps, env, info, name),
+        ExecuteMsg::OfferTransfer { name, to } => execute_offer_transfer(deps, env, info, name, to),
+        ExecuteMsg::AcceptTransfer { name } => execute_accept_transfer(deps, env, info, name),
+        ExecuteMsg::CancelTransfer { name } => execute_cancel_transfer(deps, info, name),
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => execute_transfer_nft(deps, env, info, token_id, recipient),
+        ExecuteMsg::Approve {
+            spender,
+            token_id,
+            expires,
+        } => execute_approve(deps, env, info, spender, token_id, expires),
+        ExecuteMsg::Revoke { spender, token_id } => {
+            execute_revoke(deps, env, info, spender, token_id)
+        }
+        ExecuteMsg::ApproveOperator {
+            operator,
+            name,
+            can_transfer,
+            expires,
+        } => execute_approve_operator(deps, env, info, operator, name, can_transfer, expires),
+        ExecuteMsg::RevokeOperator { operator, name } => {
+            execute_revoke_operator(deps, env, info, operator, name)
+        }
+        ExecuteMsg::Reserve { pattern, claimant } => execute_reserve(deps, info, pattern, claimant),
+        ExecuteMsg::Unreserve { pattern } => execute_unreserve(deps, info, pattern),
     }
 }
 
 pub fn execute_register(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     name: String,
 ) -> Result<Response, ContractError> {
     // we only need to check here - at point of registration
-    validate_name(&name)?;
     let config_state = config(deps.storage).load()?;
-    assert_sent_sufficient_coin(&info.funds, config_state.purchase_price)?;
+    let name = normalize_name(&config_state, name)?;
+    if config_state.auction.is_some() {
+        return Err(ContractError::AuctionOnly {});
+    }
+    assert_not_reserved(deps.as_ref(), &config_state, &info, &name)?;
+    let price = config_state.price_for(&name);
+    let refund = collect_payment(&config_state, &info, price.as_ref())?;
 
+    register_name(deps.storage, &env, &config_state, name, info.sender)?;
+
+    Ok(Response::new().add_messages(refund))
+}
+
+/// Registers a top level name to `owner` if it is available
+fn register_name(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config_state: &Config,
+    name: String,
+    owner: Addr,
+) -> Result<(), ContractError> {
     let key = name.as_bytes();
-    let record = NameRecord { owner: info.sender };
+    let mut record = NameRecord {
+        owner,
+        expires: config_state
+            .registration_period
+            .map(|period| env.block.time.plus_seconds(period)),
+        reclaimable: false,
+        approvals: vec![],
+        operators: vec![],
+        generation: 0,
+        parent_generation: 0,
+        subdomain: false,
+    };
 
-    if (resolver(deps.storage).may_load(key)?).is_some() {
-        // name is already taken
-        return Err(ContractError::NameTaken { name });
+    if let Some(existing) = resolver(storage).may_load(key)? {
+        // name is taken unless it expired and the grace period is over
+        if !existing.is_available(&env.block, config_state.grace_period) {
+            return Err(ContractError::NameTaken { name });
+        }
+        clear_primary_name(storage, &existing.owner, &name);
+        owner_names(storage, &existing.owner).remove(key);
+        // subdomains of the previous registration do not carry over
+        record.generation = existing.generation + 1;
     }
 
-    // name is available
-    resolver(deps.storage).save(key, &record)?;
+    // name is available, records and listings of a previous owner do not carry over
+    resolver(storage).save(key, &record)?;
+    records(storage).remove(key);
+    listings(storage).remove(key);
+    transfer_offers(storage).remove(key);
+    owner_names(storage, &record.owner).save(key, &Empty {})?;
 
-    Ok(Response::default())
+    Ok(())
+}
+
+/// Places a bid on an available name. The first bid has to cover the price
+/// of the name and opens the auction, later bids have to beat the current
+/// bid by the minimum increment. The previous bidder is refunded.
+pub fn execute_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let config_state = config(deps.storage).load()?;
+    let name = normalize_name(&config_state, name)?;
+    let auction_config = config_state
+        .auction
+        .as_ref()
+        .ok_or_else(|| ContractError::NoAuction { name: name.clone() })?;
+    assert_not_reserved(deps.as_ref(), &config_state, &info, &name)?;
+
+    let key = name.as_bytes();
+    if let Some(existing) = resolver(deps.storage).may_load(key)? {
+        if !existing.is_available(&env.block, config_state.grace_period) {
+            return Err(ContractError::NameTaken { name });
+        }
+    }
+
+    let denom = &auction_config.min_increment.denom;
+    let current = auctions(deps.storage).may_load(key)?;
+    let min_bid = match &current {
+        Some(auction) if auction.is_ended(&env.block) => {
+            return Err(ContractError::AuctionEnded { name });
+        }
+        Some(auction) => auction.bid.amount + auction_config.min_increment.amount,
+        // the price of the name is the reserve of the auction
+        None => config_state
+            .price_for(&name)
+            .map(|price| price.amount)
+            .unwrap_or_default(),
+    };
+    let bid = info
+        .funds
+        .iter()
+        .find(|coin| &coin.denom == denom)
+        .cloned()
+        .unwrap_or_else(|| coin(0, denom));
+    if bid.amount.is_zero() || bid.amount < min_bid {
+        return Err(ContractError::BidTooLow { min_bid });
+    }
+    let refund = collect_payment(&config_state, &info, Some(&bid))?;
+
+    let ends = match &current {
+        Some(auction) => auction.ends,
+        None => env.block.time.plus_seconds(auction_config.duration),
+    };
+    let auction = Auction {
+        bidder: info.sender,
+        bid,
+        ends,
+    };
+    auctions(deps.storage).save(key, &auction)?;
+    add_escrow(deps.storage, &auction.bid)?;
+    if let Some(previous) = &current {
+        release_escrow(deps.storage, &previous.bid)?;
+    }
+
+    // outbid funds go back to the previous bidder
+    let outbid_refund = current.map(|previous| BankMsg::Send {
+        to_address: previous.bidder.to_string(),
+        amount: vec![previous.bid],
+    });
+
+    Ok(Response::new()
+        .add_messages(refund)
+        .add_messages(outbid_refund)
+        .add_attribute("action", "bid")
+        .add_attribute("name", name)
+        .add_attribute("bidder", auction.bidder)
+        .add_attribute("bid", auction.bid.to_string()))
+}
+
+/// Settles an auction that has ended, the name goes to the highest bidder.
+/// Anyone can settle so a name is never stuck in an unclaimed auction.
+pub fn execute_claim_auction(
+    deps: DepsMut,
+    env: Env,
+    name: String,
+) -> Result<Response, ContractError> {
+    let config_state = config(deps.storage).load()?;
+    let name = normalize_name(&config_state, name)?;
+    let key = name.as_bytes();
+    let auction = auctions(deps.storage)
+        .may_load(key)?
+        .ok_or_else(|| ContractError::NoAuction { name: name.clone() })?;
+    if !auction.is_ended(&env.block) {
+        return Err(ContractError::AuctionNotEnded { name });
+    }
+
+    auctions(deps.storage).remove(key);
+    // the winning bid is now collected like any other purchase price
+    release_escrow(deps.storage, &auction.bid)?;
+    register_name(
+        deps.storage,
+        &env,
+        &config_state,
+        name.clone(),
+        auction.bidder.clone(),
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_auction")
+        .add_attribute("name", name)
+        .add_attribute("owner", auction.bidder))
 }
 
 pub fn execute_transfer(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     name: String,
     to: String,
 ) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
     let config_state = config(deps.storage).load()?;
-    assert_sent_sufficient_coin(&info.funds, config_state.transfer_price)?;
+    let refund = collect_payment(&config_state, &info, config_state.transfer_price.as_ref())?;
 
     let new_owner = deps.api.addr_validate(&to)?;
+    // subdomains stay with their owners when the parent name changes hands
+    let record = assert_can_transfer(deps.as_ref(), &env, &info, &name)?;
+    change_owner(deps.storage, &name, record, new_owner)?;
+    Ok(Response::new().add_messages(refund))
+}
+
+pub fn execute_transfer_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let record = assert_can_transfer(deps.as_ref(), &env, &info, &name)?;
+    change_owner(deps.storage, &name, record, recipient.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_nft")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("token_id", name))
+}
+
+pub fn execute_offer_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    to: String,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    assert_name_owner(deps.as_ref(), &env, &info, &name)?;
+
+    let offer = TransferOffer {
+        from: info.sender,
+        to: deps.api.addr_validate(&to)?,
+    };
+    transfer_offers(deps.storage).save(name.as_bytes(), &offer)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "offer_transfer")
+        .add_attribute("name", name)
+        .add_attribute("to", offer.to))
+}
+
+/// Moves an offered name to the recipient, who pays the transfer price
+pub fn execute_accept_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    let config_state = config(deps.storage).load()?;
+    let (offer, record) = load_valid_offer(deps.as_ref(), &env.block, &name)?
+        .ok_or_else(|| ContractError::NoTransferOffer { name: name.clone() })?;
+    if info.sender != offer.to {
+        return Err(ContractError::Unauthorized {});
+    }
+    let refund = collect_payment(&config_state, &info, config_state.transfer_price.as_ref())?;
+
+    change_owner(deps.storage, &name, record, offer.to.clone())?;
+
+    Ok(Response::new()
+        .add_messages(refund)
+        .add_attribute("action", "accept_transfer")
+        .add_attribute("name", name)
+        .add_attribute("from", offer.from)
+        .add_attribute("to", offer.to))
+}
+
+pub fn execute_cancel_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
     let key = name.as_bytes();
-    resolver(deps.storage).update(key, |record| {
-        if let Some(mut record) = record {
-            if info.sender != record.owner {
-                return Err(ContractError::Unauthorized {});
-            }
+    let offer = transfer_offers(deps.storage)
+        .may_load(key)?
+        .ok_or_else(|| ContractError::NoTransferOffer { name: name.clone() })?;
+    if info.sender != offer.from {
+        return Err(ContractError::Unauthorized {});
+    }
+    transfer_offers(deps.storage).remove(key);
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_transfer")
+        .add_attribute("name", name))
+}
+
+/// Loads the transfer offer of a name together with its record, as long as
+/// the offering owner still owns the name and it is active
+fn load_valid_offer(
+    deps: Deps,
+    block: &BlockInfo,
+    name: &str,
+) -> StdResult<Option<(TransferOffer, NameRecord)>> {
+    let offer = match transfer_offers_read(deps.storage).may_load(name.as_bytes())? {
+        Some(offer) => offer,
+        None => return Ok(None),
+    };
+    match load_active_record(deps.storage, block, name)? {
+        Some(record) if record.owner == offer.from => Ok(Some((offer, record))),
+        _ => Ok(None),
+    }
+}
+
+pub fn execute_approve(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    name: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    let mut record = assert_name_owner(deps.as_ref(), &env, &info, &name)?;
+    let spender = deps.api.addr_validate(&spender)?;
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+
+    // a new approval replaces the previous one of the spender
+    record
+        .approvals
+        .retain(|approval| approval.spender != spender);
+    record.approvals.push(Approval {
+        spender: spender.clone(),
+        expires,
+    });
+    resolver(deps.storage).save(name.as_bytes(), &record)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve")
+        .add_attribute("token_id", name)
+        .add_attribute("spender", spender))
+}
+
+pub fn execute_revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    name: String,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    let mut record = assert_name_owner(deps.as_ref(), &env, &info, &name)?;
+    let spender = deps.api.addr_validate(&spender)?;
+
+    record
+        .approvals
+        .retain(|approval| approval.spender != spender);
+    resolver(deps.storage).save(name.as_bytes(), &record)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke")
+        .add_attribute("token_id", name)
+        .add_attribute("spender", spender))
+}
+
+/// Approves an operator for a name, or for all names of the sender.
+/// A new approval replaces the previous one of the operator.
+pub fn execute_approve_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    name: Option<String>,
+    can_transfer: bool,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let operator = Operator {
+        operator: deps.api.addr_validate(&operator)?,
+        can_transfer,
+        expires: expires.unwrap_or_default(),
+    };
+    if operator.expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
 
-            record.owner = new_owner.clone();
-            Ok(record)
-        } else {
-            Err(ContractError::NameNotExists { name: name.clone() })
+    let response = match name {
+        Some(name) => {
+            let name = lookup_name(deps.storage, name)?;
+            let mut record = assert_name_owner(deps.as_ref(), &env, &info, &name)?;
+            record
+                .operators
+                .retain(|approved| approved.operator != operator.operator);
+            record.operators.push(operator.clone());
+            resolver(deps.storage).save(name.as_bytes(), &record)?;
+            Response::new().add_attribute("name", name)
         }
-    })?;
-    Ok(Response::default())
+        None => {
+            owner_operators(deps.storage, &info.sender)
+                .save(operator.operator.as_bytes(), &operator)?;
+            Response::new().add_attribute("owner", info.sender)
+        }
+    };
+
+    Ok(response
+        .add_attribute("action", "approve_operator")
+        .add_attribute("operator", operator.operator))
+}
+
+pub fn execute_revoke_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    name: Option<String>,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+
+    let response = match name {
+        Some(name) => {
+            let name = lookup_name(deps.storage, name)?;
+            let mut record = assert_name_owner(deps.as_ref(), &env, &info, &name)?;
+            record
+                .operators
+                .retain(|approved| approved.operator != operator);
+            resolver(deps.storage).save(name.as_bytes(), &record)?;
+            Response::new().add_attribute("name", name)
+        }
+        None => {
+            owner_operators(deps.storage, &info.sender).remove(operator.as_bytes());
+            Response::new().add_attribute("owner", info.sender)
+        }
+    };
+
+    Ok(response
+        .add_attribute("action", "revoke_operator")
+        .add_attribute("operator", operator))
+}
+
+/// Moves a name to a new owner, who starts with a clean set of records
+fn change_owner(
+    storage: &mut dyn Storage,
+    name: &str,
+    mut record: NameRecord,
+    new_owner: Addr,
+) -> StdResult<()> {
+    let key = name.as_bytes();
+    let previous_owner = std::mem::replace(&mut record.owner, new_owner);
+    // approvals and operators were given by the previous owner
+    record.approvals.clear();
+    record.operators.clear();
+    resolver(storage).save(key, &record)?;
+    records(storage).remove(key);
+    listings(storage).remove(key);
+    transfer_offers(storage).remove(key);
+    clear_primary_name(storage, &previous_owner, name);
+    owner_names(storage, &previous_owner).remove(key);
+    owner_names(storage, &record.owner).save(key, &Empty {})?;
+    Ok(())
+}
+
+/// Extends the registration of a name by another registration period.
+/// Only the owner can renew, which is still possible during the grace period.
+pub fn execute_renew(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    let config_state = config(deps.storage).load()?;
+    let period = config_state
+        .registration_period
+        .ok_or(ContractError::RenewalDisabled {})?;
+    let refund = collect_payment(&config_state, &info, config_state.renewal_price.as_ref())?;
+
+    let key = name.as_bytes();
+    let mut record = resolver(deps.storage)
+        .may_load(key)?
+        .ok_or_else(|| ContractError::NameNotExists { name: name.clone() })?;
+    // subdomains do not expire on their own, they live as long as their parent
+    if record.subdomain {
+        return Err(ContractError::RenewalDisabled {});
+    }
+    let is_manager = info.sender == record.owner
+        || is_operator(deps.storage, &env.block, &record, &info.sender, false)?;
+    if !is_manager || record.is_available(&env.block, config_state.grace_period) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // renewing extends the current expiration, names without one start from now
+    let expires = record
+        .expires
+        .unwrap_or(env.block.time)
+        .plus_seconds(period);
+    record.expires = Some(expires);
+    resolver(deps.storage).save(key, &record)?;
+
+    Ok(Response::new()
+        .add_messages(refund)
+        .add_attribute("action", "renew")
+        .add_attribute("name", name)
+        .add_attribute("expires", expires.seconds().to_string()))
+}
+
+pub fn execute_set_record(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    record: Record,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    let key = name.as_bytes();
+    assert_name_manager(deps.as_ref(), &env, &info, &name)?;
+
+    let mut name_records = records(deps.storage).may_load(key)?.unwrap_or_default();
+    let record_key = match record {
+        Record::Address(address) => {
+            name_records.address = Some(deps.api.addr_validate(&address)?);
+            "address"
+        }
+        Record::Url(url) => {
+            validate_record_length("url", &url, MAX_URL_LENGTH)?;
+            name_records.url = Some(url);
+            "url"
+        }
+        Record::Avatar(avatar) => {
+            validate_record_length("avatar", &avatar, MAX_AVATAR_LENGTH)?;
+            name_records.avatar = Some(avatar);
+            "avatar"
+        }
+        Record::Email(email) => {
+            validate_record_length("email", &email, MAX_EMAIL_LENGTH)?;
+            name_records.email = Some(email);
+            "email"
+        }
+        Record::ContentHash(content_hash) => {
+            validate_record_length("content_hash", &content_hash, MAX_CONTENT_HASH_LENGTH)?;
+            name_records.content_hash = Some(content_hash);
+            "content_hash"
+        }
+    };
+    records(deps.storage).save(key, &name_records)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_record")
+        .add_attribute("name", name)
+        .add_attribute("key", record_key))
+}
+
+pub fn execute_remove_record(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    record_key: RecordKey,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    let key = name.as_bytes();
+    assert_name_manager(deps.as_ref(), &env, &info, &name)?;
+
+    let mut name_records = records(deps.storage).may_load(key)?.unwrap_or_default();
+    match record_key {
+        RecordKey::Address => name_records.address = None,
+        RecordKey::Url => name_records.url = None,
+        RecordKey::Avatar => name_records.avatar = None,
+        RecordKey::Email => name_records.email = None,
+        RecordKey::ContentHash => name_records.content_hash = None,
+    }
+    if name_records.is_empty() {
+        records(deps.storage).remove(key);
+    } else {
+        records(deps.storage).save(key, &name_records)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_record")
+        .add_attribute("name", name))
+}
+
+pub fn execute_set_primary_name(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    assert_name_owner(deps.as_ref(), &env, &info, &name)?;
+    reverse(deps.storage).save(info.sender.as_bytes(), &name)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_primary_name")
+        .add_attribute("address", info.sender)
+        .add_attribute("name", name))
+}
+
+pub fn execute_remove_primary_name(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    reverse(deps.storage).remove(info.sender.as_bytes());
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_primary_name")
+        .add_attribute("address", info.sender))
+}
+
+pub fn execute_create_subdomain(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    parent: String,
+    label: String,
+    owner: String,
+    reclaimable: bool,
+) -> Result<Response, ContractError> {
+    let parent = lookup_name(deps.storage, parent)?;
+    // every label follows the same rules as a top level name
+    let label = normalize_name(&config(deps.storage).load()?, label)?;
+    let parent_record = assert_name_owner(deps.as_ref(), &env, &info, &parent)?;
+    let owner = deps.api.addr_validate(&owner)?;
+
+    let name = format!("{}.{}", label, parent);
+    let length = name.len() as u64;
+    if length > MAX_FULL_NAME_LENGTH {
+        return Err(ContractError::NameTooLong {
+            length,
+            max_length: MAX_FULL_NAME_LENGTH,
+        });
+    }
+    let key = name.as_bytes();
+    if load_active_record(deps.storage, &env.block, &name)?.is_some() {
+        return Err(ContractError::NameTaken { name });
+    }
+
+    let mut record = NameRecord {
+        owner,
+        expires: None,
+        reclaimable,
+        approvals: vec![],
+        operators: vec![],
+        generation: 0,
+        parent_generation: parent_record.generation,
+        subdomain: true,
+    };
+    // a subdomain left over from a previous registration of the parent is replaced
+    if let Some(stale) = resolver(deps.storage).may_load(key)? {
+        clear_primary_name(deps.storage, &stale.owner, &name);
+        owner_names(deps.storage, &stale.owner).remove(key);
+        records(deps.storage).remove(key);
+        listings(deps.storage).remove(key);
+        transfer_offers(deps.storage).remove(key);
+        record.generation = stale.generation + 1;
+    }
+    resolver(deps.storage).save(key, &record)?;
+    owner_names(deps.storage, &record.owner).save(key, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_subdomain")
+        .add_attribute("name", name)
+        .add_attribute("owner", record.owner))
+}
+
+pub fn execute_reclaim_subdomain(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    let parent = match name.split_once('.') {
+        Some((_, parent)) => parent,
+        None => return Err(ContractError::NotSubdomain { name }),
+    };
+    assert_name_owner(deps.as_ref(), &env, &info, parent)?;
+
+    let record = load_active_record(deps.storage, &env.block, &name)?
+        .ok_or_else(|| ContractError::NameNotExists { name: name.clone() })?;
+    if !record.subdomain {
+        return Err(ContractError::NotSubdomain { name });
+    }
+    if !record.reclaimable {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    change_owner(deps.storage, &name, record, info.sender.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "reclaim_subdomain")
+        .add_attribute("name", name)
+        .add_attribute("owner", info.sender))
+}
+
+pub fn execute_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Vec<Coin>,
+    to: Option<String>,
+) -> Result<Response, ContractError> {
+    let config_state = config(deps.storage).load()?;
+    let admin = assert_admin(&config_state, &info)?;
+    if amount.is_empty() || amount.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let to = match to {
+        Some(to) => deps.api.addr_validate(&to)?,
+        None => admin,
+    };
+
+    // bids of running auctions have to stay available for refunds
+    for coin in &amount {
+        let requested: Uint128 = amount
+            .iter()
+            .filter(|other| other.denom == coin.denom)
+            .map(|other| other.amount)
+            .sum();
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, &coin.denom)?;
+        let held = escrow_read(deps.storage)
+            .may_load(coin.denom.as_bytes())?
+            .unwrap_or_default();
+        let available = balance.amount.saturating_sub(held);
+        if requested > available {
+            return Err(ContractError::FundsInEscrow {
+                denom: coin.denom.clone(),
+                available,
+            });
+        }
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: to.to_string(),
+            amount,
+        })
+        .add_attribute("action", "withdraw")
+        .add_attribute("to", to))
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    purchase_price: Option<PriceUpdate>,
+    transfer_price: Option<PriceUpdate>,
+    renewal_price: Option<PriceUpdate>,
+    length_prices: Option<Vec<LengthPrice>>,
+) -> Result<Response, ContractError> {
+    let mut config_state = config(deps.storage).load()?;
+    assert_admin(&config_state, &info)?;
+
+    update_price(&mut config_state.purchase_price, purchase_price);
+    update_price(&mut config_state.transfer_price, transfer_price);
+    update_price(&mut config_state.renewal_price, renewal_price);
+    if let Some(length_prices) = length_prices {
+        config_state.length_prices = length_prices;
+    }
+    assert_auction_denom(&config_state)?;
+    config(deps.storage).save(&config_state)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn update_price(price: &mut Option<Coin>, update: Option<PriceUpdate>) {
+    match update {
+        Some(PriceUpdate::Set(new_price)) => *price = Some(new_price),
+        Some(PriceUpdate::Clear) => *price = None,
+        None => {}
+    }
+}
+
+pub fn execute_transfer_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: String,
+) -> Result<Response, ContractError> {
+    let mut config_state = config(deps.storage).load()?;
+    assert_admin(&config_state, &info)?;
+
+    let pending_admin = deps.api.addr_validate(&admin)?;
+    config_state.pending_admin = Some(pending_admin.clone());
+    config(deps.storage).save(&config_state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_admin")
+        .add_attribute("pending_admin", pending_admin))
+}
+
+pub fn execute_accept_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut config_state = config(deps.storage).load()?;
+    if config_state.pending_admin.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config_state.admin = config_state.pending_admin.take();
+    config(deps.storage).save(&config_state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_admin")
+        .add_attribute("admin", info.sender))
+}
+
+pub fn execute_renounce_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut config_state = config(deps.storage).load()?;
+    assert_admin(&config_state, &info)?;
+
+    config_state.admin = None;
+    config_state.pending_admin = None;
+    config(deps.storage).save(&config_state)?;
+
+    Ok(Response::new().add_attribute("action", "renounce_admin"))
+}
+
+/// Checks that the price was paid and returns a refund of everything sent on top of it.
+/// In strict mode any excess is rejected instead.
+fn collect_payment(
+    config_state: &Config,
+    info: &MessageInfo,
+    price: Option<&Coin>,
+) -> Result<Option<BankMsg>, ContractError> {
+    assert_sent_sufficient_coin(&info.funds, price.cloned())?;
+
+    let excess = excess_funds(&info.funds, price);
+    if excess.is_empty() {
+        Ok(None)
+    } else if config_state.strict_payment {
+        Err(ContractError::ExcessFundsSend {})
+    } else {
+        Ok(Some(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: excess,
+        }))
+    }
+}
+
+/// Auction bids are paid in the denom of the increment, so every price has to use it too
+fn assert_auction_denom(config_state: &Config) -> StdResult<()> {
+    let auction_config = match &config_state.auction {
+        Some(auction_config) => auction_config,
+        None => return Ok(()),
+    };
+    let denom = &auction_config.min_increment.denom;
+    let prices = config_state
+        .purchase_price
+        .iter()
+        .chain(config_state.length_prices.iter().map(|entry| &entry.price));
+    for price in prices {
+        if &price.denom != denom {
+            return Err(StdError::generic_err(format!(
+                "purchase prices must use the auction denom {}",
+                denom
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn add_escrow(storage: &mut dyn Storage, bid: &Coin) -> StdResult<Uint128> {
+    escrow(storage).update(bid.denom.as_bytes(), |held| {
+        Ok(held.unwrap_or_default().checked_add(bid.amount)?)
+    })
+}
+
+fn release_escrow(storage: &mut dyn Storage, bid: &Coin) -> StdResult<Uint128> {
+    escrow(storage).update(bid.denom.as_bytes(), |held| {
+        Ok(held.unwrap_or_default().checked_sub(bid.amount)?)
+    })
+}
+
+pub fn execute_reserve(
+    deps: DepsMut,
+    info: MessageInfo,
+    pattern: ReservedPattern,
+    claimant: Option<String>,
+) -> Result<Response, ContractError> {
+    let config_state = config(deps.storage).load()?;
+    assert_admin(&config_state, &info)?;
+
+    let reservation = Reservation {
+        claimant: claimant
+            .map(|claimant| deps.api.addr_validate(&claimant))
+            .transpose()?,
+    };
+    let reserved = match pattern {
+        ReservedPattern::Name(name) => {
+            let name = normalize_name(&config_state, name)?;
+            reserved_names(deps.storage).save(name.as_bytes(), &reservation)?;
+            name
+        }
+        ReservedPattern::Prefix(prefix) => {
+            let prefix = normalize_prefix(&config_state, prefix)?;
+            reserved_prefixes(deps.storage).save(prefix.as_bytes(), &reservation)?;
+            prefix
+        }
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "reserve")
+        .add_attribute("reserved", reserved))
+}
+
+pub fn execute_unreserve(
+    deps: DepsMut,
+    info: MessageInfo,
+    pattern: ReservedPattern,
+) -> Result<Response, ContractError> {
+    let config_state = config(deps.storage).load()?;
+    assert_admin(&config_state, &info)?;
+
+    let reserved = match pattern {
+        ReservedPattern::Name(name) => {
+            let name = normalize_name(&config_state, name)?;
+            reserved_names(deps.storage).remove(name.as_bytes());
+            name
+        }
+        ReservedPattern::Prefix(prefix) => {
+            let prefix = normalize_prefix(&config_state, prefix)?;
+            reserved_prefixes(deps.storage).remove(prefix.as_bytes());
+            prefix
+        }
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "unreserve")
+        .add_attribute("reserved", reserved))
+}
+
+/// Finds the reservation covering a name, exact names take precedence over prefixes
+fn load_reservation(
+    storage: &dyn Storage,
+    config_state: &Config,
+    name: &str,
+) -> StdResult<Option<Reservation>> {
+    if let Some(reservation) = reserved_names_read(storage).may_load(name.as_bytes())? {
+        return Ok(Some(reservation));
+    }
+    // prefixes are kept in unicode form
+    let label = if config_state.unicode_names {
+        idn::to_unicode_label(name)
+    } else {
+        name.to_string()
+    };
+    for (start, c) in label.char_indices() {
+        let prefix = &label.as_bytes()[..start + c.len_utf8()];
+        if let Some(reservation) = reserved_prefixes_read(storage).may_load(prefix)? {
+            return Ok(Some(reservation));
+        }
+    }
+    Ok(None)
+}
+
+/// Reserved names can only be taken by the admin or their claimant
+fn assert_not_reserved(
+    deps: Deps,
+    config_state: &Config,
+    info: &MessageInfo,
+    name: &str,
+) -> Result<(), ContractError> {
+    if let Some(reservation) = load_reservation(deps.storage, config_state, name)? {
+        let is_admin = config_state.admin.as_ref() == Some(&info.sender);
+        let is_claimant = reservation.claimant.as_ref() == Some(&info.sender);
+        if !is_admin && !is_claimant {
+            return Err(ContractError::NameReserved {
+                name: name.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the admin if it is the sender of the message
+fn assert_admin(config_state: &Config, info: &MessageInfo) -> Result<Addr, ContractError> {
+    match &config_state.admin {
+        Some(admin) if *admin == info.sender => Ok(admin.clone()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+pub fn execute_list_for_sale(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    price: Coin,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    assert_name_owner(deps.as_ref(), &env, &info, &name)?;
+
+    let listing = Listing {
+        seller: info.sender,
+        price,
+    };
+    listings(deps.storage).save(name.as_bytes(), &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "list_for_sale")
+        .add_attribute("name", name)
+        .add_attribute("price", listing.price.to_string()))
+}
+
+pub fn execute_cancel_listing(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    let key = name.as_bytes();
+    let listing = listings(deps.storage)
+        .may_load(key)?
+        .ok_or_else(|| ContractError::NotListed { name: name.clone() })?;
+    if info.sender != listing.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+    listings(deps.storage).remove(key);
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_listing")
+        .add_attribute("name", name))
+}
+
+/// Buys a listed name. The seller is paid the price minus the sale fee,
+/// which stays in the contract, and the name moves to the buyer.
+pub fn execute_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let name = lookup_name(deps.storage, name)?;
+    let config_state = config(deps.storage).load()?;
+    let (listing, record) = load_valid_listing(deps.as_ref(), &env.block, &name)?
+        .ok_or_else(|| ContractError::NotListed { name: name.clone() })?;
+    let refund = collect_payment(&config_state, &info, Some(&listing.price))?;
+
+    let fee = listing
+        .price
+        .amount
+        .multiply_ratio(config_state.sale_fee_bps, MAX_SALE_FEE_BPS);
+    let proceeds = listing.price.amount - fee;
+    let payment = if proceeds.is_zero() {
+        None
+    } else {
+        Some(BankMsg::Send {
+            to_address: listing.seller.to_string(),
+            amount: vec![coin(proceeds.u128(), &listing.price.denom)],
+        })
+    };
+
+    change_owner(deps.storage, &name, record, info.sender.clone())?;
+
+    Ok(Response::new()
+        .add_messages(payment)
+        .add_messages(refund)
+        .add_attribute("action", "buy")
+        .add_attribute("name", name)
+        .add_attribute("seller", listing.seller)
+        .add_attribute("buyer", info.sender)
+        .add_attribute("price", listing.price.to_string()))
+}
+
+/// Loads the listing of a name together with its record, as long as the
+/// seller still owns the name and it is active
+fn load_valid_listing(
+    deps: Deps,
+    block: &BlockInfo,
+    name: &str,
+) -> StdResult<Option<(Listing, NameRecord)>> {
+    let listing = match listings_read(deps.storage).may_load(name.as_bytes())? {
+        Some(listing) => listing,
+        None => return Ok(None),
+    };
+    match load_active_record(deps.storage, block, name)? {
+        Some(record) if record.owner == listing.seller => Ok(Some((listing, record))),
+        _ => Ok(None),
+    }
+}
+
+/// Removes the primary name of `owner` if it points to `name`
+fn clear_primary_name(storage: &mut dyn Storage, owner: &Addr, name: &str) {
+    let key = owner.as_bytes();
+    if reverse_read(storage).load(key).ok().as_deref() == Some(name) {
+        reverse(storage).remove(key);
+    }
+}
+
+/// Names can only be changed by their owner while they are active
+fn assert_name_owner(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    name: &str,
+) -> Result<NameRecord, ContractError> {
+    let record = resolver_read(deps.storage)
+        .may_load(name.as_bytes())?
+        .ok_or_else(|| ContractError::NameNotExists {
+            name: name.to_string(),
+        })?;
+    if info.sender != record.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if load_active_record(deps.storage, &env.block, name)?.is_none() {
+        return Err(ContractError::NameExpired {
+            name: name.to_string(),
+        });
+    }
+    Ok(record)
+}
+
+/// Like `assert_name_owner`, but also accepts operators approved by the owner
+fn assert_name_manager(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    name: &str,
+) -> Result<NameRecord, ContractError> {
+    let record = resolver_read(deps.storage)
+        .may_load(name.as_bytes())?
+        .ok_or_else(|| ContractError::NameNotExists {
+            name: name.to_string(),
+        })?;
+    let is_operator = is_operator(deps.storage, &env.block, &record, &info.sender, false)?;
+    if info.sender != record.owner && !is_operator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if load_active_record(deps.storage, &env.block, name)?.is_none() {
+        return Err(ContractError::NameExpired {
+            name: name.to_string(),
+        });
+    }
+    Ok(record)
+}
+
+/// Like `assert_name_owner`, but also accepts spenders approved by the owner
+/// and operators allowed to transfer
+fn assert_can_transfer(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    name: &str,
+) -> Result<NameRecord, ContractError> {
+    let record = resolver_read(deps.storage)
+        .may_load(name.as_bytes())?
+        .ok_or_else(|| ContractError::NameNotExists {
+            name: name.to_string(),
+        })?;
+    let approved = record.approvals.iter().any(|approval| {
+        approval.spender == info.sender && !approval.expires.is_expired(&env.block)
+    }) || is_operator(deps.storage, &env.block, &record, &info.sender, true)?;
+    if info.sender != record.owner && !approved {
+        return Err(ContractError::Unauthorized {});
+    }
+    if load_active_record(deps.storage, &env.block, name)?.is_none() {
+        return Err(ContractError::NameExpired {
+            name: name.to_string(),
+        });
+    }
+    Ok(record)
+}
+
+/// Checks whether `sender` is an active operator of the name, approved either for
+/// the name itself or for all names of its owner. `transfer` requires the
+/// operator to be allowed to transfer.
+fn is_operator(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    record: &NameRecord,
+    sender: &Addr,
+    transfer: bool,
+) -> StdResult<bool> {
+    let allowed = |operator: &Operator| {
+        operator.operator == *sender
+            && !operator.expires.is_expired(block)
+            && (operator.can_transfer || !transfer)
+    };
+    if record.operators.iter().any(allowed) {
+        return Ok(true);
+    }
+    let operator = owner_operators_read(storage, &record.owner).may_load(sender.as_bytes())?;
+    Ok(operator.iter().any(allowed))
+}
+
+/// Loads the record of a name if it is active. A subdomain is only active while
+/// its parent name is active and still the registration the subdomain was
+/// created under.
+fn load_active_record(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    name: &str,
+) -> StdResult<Option<NameRecord>> {
+    let record = match resolver_read(storage).may_load(name.as_bytes())? {
+        Some(record) if !record.is_expired(block) => record,
+        _ => return Ok(None),
+    };
+    if !record.subdomain {
+        return Ok(Some(record));
+    }
+    let parent = match name.split_once('.') {
+        Some((_, parent)) => parent,
+        None => return Ok(None),
+    };
+    match load_active_record(storage, block, parent)? {
+        Some(parent_record) if parent_record.generation == record.parent_generation => {
+            Ok(Some(record))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn validate_record_length(key: &str, value: &str, max_length: u64) -> Result<(), ContractError> {
+    let length = value.len() as u64;
+    if length > max_length {
+        return Err(ContractError::RecordTooLong {
+            key: key.to_string(),
+            length,
+            max_length,
+        });
+    }
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -97,37 +1362,438 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::ResolveRecord { name } => query_resolver(deps, env, name),
         QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::Records { name } => query_records(deps, env, name),
+        QueryMsg::ReverseResolve { address } => query_reverse_resolve(deps, env, address),
+        QueryMsg::Price { name } => query_price(deps, name),
+        QueryMsg::Auction { name } => {
+            let name = query_name(deps, name)?;
+            to_binary(&AuctionResponse {
+                auction: auctions_read(deps.storage).may_load(name.as_bytes())?,
+            })
+        }
+        QueryMsg::Listings { start_after, limit } => query_listings(deps, env, start_after, limit),
+        QueryMsg::TransferOffer { name } => {
+            let name = query_name(deps, name)?;
+            let offer = load_valid_offer(deps, &env.block, &name)?;
+            to_binary(&TransferOfferResponse {
+                offer: offer.map(|(offer, _)| offer_info(name, offer)),
+            })
+        }
+        QueryMsg::TransferOffers {
+            recipient,
+            start_after,
+            limit,
+        } => query_transfer_offers(deps, env, recipient, start_after, limit),
+        QueryMsg::NamesByOwner {
+            owner,
+            start_after,
+            limit,
+        } => query_names_by_owner(deps, env, owner, start_after, limit),
+        QueryMsg::AllNames { start_after, limit } => to_binary(&NamesResponse {
+            names: all_names(deps, env, start_after, limit)?,
+        }),
+        QueryMsg::Operators { name } => query_operators(deps, env, name),
+        QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => query_owner_of(deps, env, token_id, include_expired.unwrap_or(false)),
+        QueryMsg::NftInfo { token_id } => query_nft_info(deps, env, token_id),
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => query_tokens(deps, env, owner, start_after, limit),
+        QueryMsg::AllTokens { start_after, limit } => to_binary(&TokensResponse {
+            tokens: all_names(deps, env, start_after, limit)?,
+        }),
+        QueryMsg::Reserved { name } => {
+            let name = query_name(deps, name)?;
+            let config_state = config_read(deps.storage).load()?;
+            let reservation = load_reservation(deps.storage, &config_state, &name)?;
+            to_binary(&ReservedResponse {
+                reserved: reservation.is_some(),
+                claimant: reservation
+                    .and_then(|reservation| reservation.claimant)
+                    .map(String::from),
+            })
+        }
     }
 }
 
-fn query_resolver(deps: Deps, _env: Env, name: String) -> StdResult<Binary> {
+fn query_resolver(deps: Deps, env: Env, name: String) -> StdResult<Binary> {
+    let name = query_name(deps, name)?;
     let key = name.as_bytes();
 
-    let address = match resolver_read(deps.storage).may_load(key)? {
-        Some(record) => Some(String::from(&record.owner)),
-        None => None,
+    let address = match load_active_record(deps.storage, &env.block, &name)? {
+        // resolves to the address record if set, the owner otherwise
+        Some(record) => {
+            let target = records_read(deps.storage)
+                .may_load(key)?
+                .and_then(|records| records.address)
+                .unwrap_or(record.owner);
+            Some(String::from(&target))
+        }
+        _ => None,
     };
     let resp = ResolveRecordResponse { address };
 
     to_binary(&resp)
 }
 
+fn query_records(deps: Deps, env: Env, name: String) -> StdResult<Binary> {
+    let name = query_name(deps, name)?;
+    let resp = match load_active_record(deps.storage, &env.block, &name)? {
+        Some(_) => records_response(deps, &name)?,
+        _ => RecordsResponse::default(),
+    };
+
+    to_binary(&resp)
+}
+
+fn records_response(deps: Deps, name: &str) -> StdResult<RecordsResponse> {
+    let name_records = records_read(deps.storage)
+        .may_load(name.as_bytes())?
+        .unwrap_or_default();
+    Ok(RecordsResponse {
+        address: name_records.address.map(String::from),
+        url: name_records.url,
+        avatar: name_records.avatar,
+        email: name_records.email,
+        content_hash: name_records.content_hash,
+    })
+}
+
+/// Loads an active name for the cw721 queries, which fail on unknown tokens
+fn load_token(deps: Deps, env: &Env, name: &str) -> StdResult<NameRecord> {
+    load_active_record(deps.storage, &env.block, name)?
+        .ok_or_else(|| StdError::not_found("NameRecord"))
+}
+
+fn query_owner_of(deps: Deps, env: Env, name: String, include_expired: bool) -> StdResult<Binary> {
+    let name = query_name(deps, name)?;
+    let record = load_token(deps, &env, &name)?;
+    let approvals = record
+        .approvals
+        .into_iter()
+        .filter(|approval| include_expired || !approval.expires.is_expired(&env.block))
+        .map(|approval| Cw721Approval {
+            spender: approval.spender.to_string(),
+            expires: approval.expires,
+        })
+        .collect();
+
+    to_binary(&OwnerOfResponse {
+        owner: record.owner.to_string(),
+        approvals,
+    })
+}
+
+fn query_nft_info(deps: Deps, env: Env, name: String) -> StdResult<Binary> {
+    let name = query_name(deps, name)?;
+    let record = load_token(deps, &env, &name)?;
+    let records = records_response(deps, &name)?;
+
+    to_binary(&NftInfoResponse {
+        token_uri: records.url.clone(),
+        extension: NameMetadata {
+            expires: record.expires,
+            records,
+        },
+    })
+}
+
+fn query_tokens(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let owner = deps.api.addr_validate(&owner)?;
+    to_binary(&TokensResponse {
+        tokens: names_by_owner(deps, env, &owner, start_after, limit)?,
+    })
+}
+
+fn query_reverse_resolve(deps: Deps, env: Env, address: String) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+
+    // the primary name is only reported while the address owns it and it has not expired
+    let name = match reverse_read(deps.storage).may_load(address.as_bytes())? {
+        Some(name) => match load_active_record(deps.storage, &env.block, &name)? {
+            Some(record) if record.owner == address => Some(name),
+            _ => None,
+        },
+        None => None,
+    };
+    let resp = ReverseResolveResponse { name };
+
+    to_binary(&resp)
+}
+
+fn query_price(deps: Deps, name: String) -> StdResult<Binary> {
+    let name = query_name(deps, name)?;
+    let config_state = config_read(deps.storage).load()?;
+    let resp = PriceResponse {
+        price: config_state.price_for(&name),
+    };
+
+    to_binary(&resp)
+}
+
+fn query_listings(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    // listings left behind by a previous owner or of expired names are skipped
+    let listings = listings_read(deps.storage)
+        .range(start.as_deref(), None, Order::Ascending)
+        .map(|item| {
+            let (key, listing) = item?;
+            let name = String::from_utf8(key)?;
+            let record = load_active_record(deps.storage, &env.block, &name)?;
+            Ok((name, listing, record))
+        })
+        .filter_map(|item: StdResult<_>| match item {
+            Ok((name, listing, Some(record))) if record.owner == listing.seller => {
+                Some(Ok(ListingInfo {
+                    name,
+                    seller: listing.seller.to_string(),
+                    price: listing.price,
+                }))
+            }
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&ListingsResponse { listings })
+}
+
+fn query_operators(deps: Deps, env: Env, name: String) -> StdResult<Binary> {
+    let name = query_name(deps, name)?;
+    let record = load_token(deps, &env, &name)?;
+    let operator_info = |operator: Operator, all_names: bool| OperatorInfo {
+        operator: operator.operator.to_string(),
+        all_names,
+        can_transfer: operator.can_transfer,
+        expires: operator.expires,
+    };
+
+    let all_names = owner_operators_read(deps.storage, &record.owner)
+        .range(None, None, Order::Ascending)
+        .map(|item| item.map(|(_, operator)| operator))
+        .collect::<StdResult<Vec<_>>>()?;
+    let operators = record
+        .operators
+        .into_iter()
+        .map(|operator| operator_info(operator, false))
+        .chain(
+            all_names
+                .into_iter()
+                .map(|operator| operator_info(operator, true)),
+        )
+        .filter(|operator| !operator.expires.is_expired(&env.block))
+        .collect();
+
+    to_binary(&OperatorsResponse { operators })
+}
+
+fn query_transfer_offers(
+    deps: Deps,
+    env: Env,
+    recipient: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    // offers left behind by a previous owner or of expired names are skipped
+    let offers = transfer_offers_read(deps.storage)
+        .range(start.as_deref(), None, Order::Ascending)
+        .map(|item| {
+            let (key, offer) = item?;
+            let name = String::from_utf8(key)?;
+            let record = if offer.to == recipient {
+                load_active_record(deps.storage, &env.block, &name)?
+            } else {
+                None
+            };
+            Ok((name, offer, record))
+        })
+        .filter_map(|item: StdResult<_>| match item {
+            Ok((name, offer, Some(record))) if record.owner == offer.from => {
+                Some(Ok(offer_info(name, offer)))
+            }
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&TransferOffersResponse { offers })
+}
+
+fn offer_info(name: String, offer: TransferOffer) -> TransferOfferInfo {
+    TransferOfferInfo {
+        name,
+        from: offer.from.to_string(),
+        to: offer.to.to_string(),
+    }
+}
+
+fn query_names_by_owner(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let owner = deps.api.addr_validate(&owner)?;
+    to_binary(&NamesResponse {
+        names: names_by_owner(deps, env, &owner, start_after, limit)?,
+    })
+}
+
+fn names_by_owner(
+    deps: Deps,
+    env: Env,
+    owner: &Addr,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    // expired names stay in the index until they are registered again
+    owner_names_read(deps.storage, owner)
+        .range(start.as_deref(), None, Order::Ascending)
+        .map(|item| {
+            let (key, _) = item?;
+            let name = String::from_utf8(key)?;
+            let record = load_active_record(deps.storage, &env.block, &name)?;
+            Ok((name, record))
+        })
+        .filter_map(|item: StdResult<_>| match item {
+            Ok((name, Some(record))) if record.owner == *owner => Some(Ok(name)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .take(limit)
+        .collect()
+}
+
+fn all_names(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    resolver_read(deps.storage)
+        .range(start.as_deref(), None, Order::Ascending)
+        .map(|item| {
+            let (key, _) = item?;
+            let name = String::from_utf8(key)?;
+            let record = load_active_record(deps.storage, &env.block, &name)?;
+            Ok((name, record))
+        })
+        .filter_map(|item: StdResult<_>| match item {
+            Ok((name, Some(_))) => Some(Ok(name)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Range starts are inclusive, so the key after `start_after` is the start
+fn calc_range_start(start_after: Option<String>) -> Option<Vec<u8>> {
+    start_after.map(|name| {
+        let mut key = name.into_bytes();
+        key.push(0);
+        key
+    })
+}
+
 // let's not import a regexp library and just do these checks by hand
 fn invalid_char(c: char) -> bool {
-    let is_valid = c.is_digit(10) || c.is_ascii_lowercase() || (c == '.' || c == '-' || c == '_');
+    let is_valid = c.is_digit(10) || c.is_ascii_lowercase() || (c == '-' || c == '_');
     !is_valid
 }
 
+/// normalize_name validates the name and returns the form it is stored in,
+/// unicode names are normalized and punycode encoded if they are enabled
+fn normalize_name(config_state: &Config, name: String) -> Result<String, ContractError> {
+    if config_state.unicode_names {
+        let label = idn::to_ascii_label(&name)?;
+        validate_name(&label, idn::label_length(&label))?;
+        Ok(label)
+    } else {
+        validate_name(&name, name.len() as u64)?;
+        Ok(name)
+    }
+}
+
+/// lookup_name returns the form a name is stored in without validating it, so names
+/// registered under earlier rules are still found. Labels are normalized and punycode
+/// encoded like in normalize_name if unicode names are enabled.
+fn lookup_name(storage: &dyn Storage, name: String) -> Result<String, ContractError> {
+    if !config_read(storage).load()?.unicode_names {
+        return Ok(name);
+    }
+    let labels = name
+        .split('.')
+        .map(|label| {
+            if label.is_ascii() {
+                Ok(label.to_ascii_lowercase())
+            } else {
+                idn::to_ascii_label(label)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(labels.join("."))
+}
+
+/// query_name is lookup_name for queries
+fn query_name(deps: Deps, name: String) -> StdResult<String> {
+    lookup_name(deps.storage, name).map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+/// normalize_prefix validates a reserved prefix and returns the form it is stored in
+fn normalize_prefix(config_state: &Config, prefix: String) -> Result<String, ContractError> {
+    if config_state.unicode_names {
+        return idn::normalize_prefix(&prefix);
+    }
+    match prefix.chars().find(|&c| invalid_char(c)) {
+        None => Ok(prefix),
+        Some(c) => Err(ContractError::InvalidCharacter {
+            c,
+            reason: InvalidCharacterReason::NotAllowed,
+        }),
+    }
+}
+
 /// validate_name returns an error if the name is invalid
-/// (we require 3-64 lowercase ascii letters, numbers, or . - _)
-fn validate_name(name: &str) -> Result<(), ContractError> {
-    let length = name.len() as u64;
-    if (name.len() as u64) < MIN_NAME_LENGTH {
+/// (we require 3-64 lowercase ascii letters, numbers, or - _)
+/// '.' separates the labels of subdomains and is not allowed within a name
+fn validate_name(name: &str, length: u64) -> Result<(), ContractError> {
+    if length < MIN_NAME_LENGTH {
         Err(ContractError::NameTooShort {
             length,
             min_length: MIN_NAME_LENGTH,
         })
-    } else if (name.len() as u64) > MAX_NAME_LENGTH {
+    } else if length > MAX_NAME_LENGTH {
         Err(ContractError::NameTooLong {
             length,
             max_length: MAX_NAME_LENGTH,
@@ -137,7 +1803,10 @@ fn validate_name(name: &str) -> Result<(), ContractError> {
             None => Ok(()),
             Some(bytepos_invalid_char_start) => {
                 let c = name[bytepos_invalid_char_start..].chars().next().unwrap();
-                Err(ContractError::InvalidCharacter { c })
+                Err(ContractError::InvalidCharacter {
+                    c,
+                    reason: InvalidCharacterReason::NotAllowed,
+                })
             }
         }
     }
@@ -1,4 +1,6 @@
-use cosmwasm_std::StdError;
+use std::fmt;
+
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,9 +14,54 @@ pub enum ContractError {
     #[error("Insufficient funds sent")]
     InsufficientFundsSend {},
 
+    #[error("Sent more funds than required")]
+    ExcessFundsSend {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
     #[error("Name does not exist (name {name})")]
     NameNotExists { name: String },
 
+    #[error("Name has expired (name {name})")]
+    NameExpired { name: String },
+
+    #[error("Names do not expire, nothing to renew")]
+    RenewalDisabled {},
+
+    #[error("Name is not a subdomain (name {name})")]
+    NotSubdomain { name: String },
+
+    #[error("Names are only sold by auction")]
+    AuctionOnly {},
+
+    #[error("Auction has ended (name {name})")]
+    AuctionEnded { name: String },
+
+    #[error("Auction has not ended yet (name {name})")]
+    AuctionNotEnded { name: String },
+
+    #[error("No auction for name (name {name})")]
+    NoAuction { name: String },
+
+    #[error("Bid too low (min_bid {min_bid})")]
+    BidTooLow { min_bid: Uint128 },
+
+    #[error("Funds are held for running auctions (denom {denom}, available {available})")]
+    FundsInEscrow { denom: String, available: Uint128 },
+
+    #[error("Name is not listed for sale (name {name})")]
+    NotListed { name: String },
+
+    #[error("Name has not been offered (name {name})")]
+    NoTransferOffer { name: String },
+
+    #[error("Approval has already expired")]
+    ApprovalExpired {},
+
+    #[error("Name is reserved (name {name})")]
+    NameReserved { name: String },
+
     #[error("Name has been taken (name {name})")]
     NameTaken { name: String },
 
@@ -24,6 +71,42 @@ pub enum ContractError {
     #[error("Name too long (length {length} min_length {max_length})")]
     NameTooLong { length: u64, max_length: u64 },
 
-    #[error("Invalid character(char {c}")]
-    InvalidCharacter { c: char },
+    #[error("Record too long (key {key} length {length} max_length {max_length})")]
+    RecordTooLong {
+        key: String,
+        length: u64,
+        max_length: u64,
+    },
+
+    #[error("Invalid character(char {c} reason {reason})")]
+    InvalidCharacter {
+        c: char,
+        reason: InvalidCharacterReason,
+    },
+
+    #[error("Invalid punycode label (label {label})")]
+    InvalidPunycode { label: String },
+}
+
+/// Why a character is not accepted in a name
+#[derive(Clone, Debug, PartialEq)]
+pub enum InvalidCharacterReason {
+    /// Not a letter, number, '-' or '_'
+    NotAllowed,
+    /// Of another script than the characters before it
+    MixedScript,
+    /// Makes the label look like the ascii label `skeleton`
+    Confusable { skeleton: String },
+}
+
+impl fmt::Display for InvalidCharacterReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidCharacterReason::NotAllowed => write!(f, "not allowed"),
+            InvalidCharacterReason::MixedScript => write!(f, "mixed script"),
+            InvalidCharacterReason::Confusable { skeleton } => {
+                write!(f, "confusable with {}", skeleton)
+            }
+        }
+    }
 }
@@ -0,0 +1,110 @@
+use idna::punycode;
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::mixed_script::AugmentedScriptSet;
+use unicode_security::{skeleton, GeneralSecurityProfile};
+
+use crate::error::{ContractError, InvalidCharacterReason};
+
+/// Prefix of labels stored in punycode form
+pub const ACE_PREFIX: &str = "xn--";
+
+/// Normalizes a label with NFC and lowercasing and returns the form it is stored in:
+/// ascii labels are kept as they are, all others are punycode encoded.
+/// Labels already in punycode form are accepted if they are canonical.
+pub fn to_ascii_label(label: &str) -> Result<String, ContractError> {
+    let unicode = match label.strip_prefix(ACE_PREFIX) {
+        Some(encoded) => {
+            punycode::decode_to_string(encoded).ok_or_else(|| ContractError::InvalidPunycode {
+                label: label.to_string(),
+            })?
+        }
+        None => label.to_string(),
+    };
+    let normalized: String = unicode.to_lowercase().nfc().collect();
+    if normalized.is_ascii() {
+        if label.starts_with(ACE_PREFIX) {
+            return Err(ContractError::InvalidPunycode {
+                label: label.to_string(),
+            });
+        }
+        return Ok(normalized);
+    }
+
+    validate_unicode_label(&normalized)?;
+    let encoded = punycode::encode_str(&normalized)
+        .map(|encoded| format!("{}{}", ACE_PREFIX, encoded))
+        .ok_or_else(|| ContractError::InvalidPunycode {
+            label: label.to_string(),
+        })?;
+    if label.starts_with(ACE_PREFIX) && encoded != label {
+        return Err(ContractError::InvalidPunycode {
+            label: label.to_string(),
+        });
+    }
+    Ok(encoded)
+}
+
+/// Normalizes a prefix with NFC and lowercasing. It is kept in unicode form
+/// as the punycode of a label does not start with the punycode of its prefixes.
+pub fn normalize_prefix(prefix: &str) -> Result<String, ContractError> {
+    let normalized: String = prefix.to_lowercase().nfc().collect();
+    if let Some(c) = normalized.chars().find(|&c| !valid_unicode_char(c)) {
+        return Err(ContractError::InvalidCharacter {
+            c,
+            reason: InvalidCharacterReason::NotAllowed,
+        });
+    }
+    Ok(normalized)
+}
+
+/// Unicode form of a stored label
+pub fn to_unicode_label(label: &str) -> String {
+    label
+        .strip_prefix(ACE_PREFIX)
+        .and_then(punycode::decode_to_string)
+        .unwrap_or_else(|| label.to_string())
+}
+
+/// Number of characters of a label, punycode labels are counted in their unicode form
+pub fn label_length(label: &str) -> u64 {
+    to_unicode_label(label).chars().count() as u64
+}
+
+/// Only letters and numbers allowed in identifiers by UTS #39 are accepted,
+/// they have to be of a single script and must not be confusable with an ascii label
+fn validate_unicode_label(label: &str) -> Result<(), ContractError> {
+    if let Some(c) = label.chars().find(|&c| !valid_unicode_char(c)) {
+        return Err(ContractError::InvalidCharacter {
+            c,
+            reason: InvalidCharacterReason::NotAllowed,
+        });
+    }
+    let mut scripts = AugmentedScriptSet::default();
+    for c in label.chars() {
+        scripts.intersect_with(AugmentedScriptSet::for_char(c));
+        if scripts.is_empty() {
+            return Err(ContractError::InvalidCharacter {
+                c,
+                reason: InvalidCharacterReason::MixedScript,
+            });
+        }
+    }
+    let skeleton: String = skeleton(label).collect();
+    if skeleton.is_ascii() {
+        // the label is not ascii, so there is a character to blame
+        let c = label.chars().find(|c| !c.is_ascii()).unwrap_or_default();
+        return Err(ContractError::InvalidCharacter {
+            c,
+            reason: InvalidCharacterReason::Confusable { skeleton },
+        });
+    }
+    Ok(())
+}
+
+fn valid_unicode_char(c: char) -> bool {
+    if c.is_ascii() {
+        c.is_ascii_digit() || c.is_ascii_lowercase() || c == '-' || c == '_'
+    } else {
+        c.is_alphanumeric() && c.identifier_allowed()
+    }
+}
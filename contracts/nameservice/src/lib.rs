@@ -1,10 +1,11 @@
 pub mod coin_helpers;
 pub mod contract;
 mod error;
+pub mod idn;
 pub mod msg;
 pub mod state;
 
 #[cfg(test)]
 mod tests;
 
-pub use crate::error::ContractError;
+pub use crate::error::{ContractError, InvalidCharacterReason};
@@ -1,26 +1,290 @@
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Coin, Timestamp};
+use cw721::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::state::{Auction, AuctionConfig, LengthPrice};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub purchase_price: Option<Coin>,
     pub transfer_price: Option<Coin>,
+    /// Seconds a registration or renewal lasts. Names never expire if unset.
+    #[serde(default)]
+    pub registration_period: Option<u64>,
+    #[serde(default)]
+    pub renewal_price: Option<Coin>,
+    /// Seconds after expiration during which only the owner can renew the name
+    #[serde(default)]
+    pub grace_period: u64,
+    /// Defaults to the instantiator
+    #[serde(default)]
+    pub admin: Option<String>,
+    /// Rejects payments above the price instead of refunding the excess
+    #[serde(default)]
+    pub strict_payment: bool,
+    /// Purchase prices by name length, `purchase_price` applies to all other names
+    #[serde(default)]
+    pub length_prices: Vec<LengthPrice>,
+    /// Sells names by auction, `Register` is disabled if set
+    #[serde(default)]
+    pub auction: Option<AuctionConfig>,
+    /// Share of marketplace sales kept by the contract, in basis points
+    #[serde(default)]
+    pub sale_fee_bps: u64,
+    /// Accepts unicode names. They are normalized and stored in punycode form,
+    /// which all other messages and queries refer to them by.
+    #[serde(default)]
+    pub unicode_names: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    Register { name: String },
-    Transfer { name: String, to: String },
+    Register {
+        name: String,
+    },
+    Transfer {
+        name: String,
+        to: String,
+    },
+    Renew {
+        name: String,
+    },
+    SetRecord {
+        name: String,
+        record: Record,
+    },
+    RemoveRecord {
+        name: String,
+        key: RecordKey,
+    },
+    SetPrimaryName {
+        name: String,
+    },
+    RemovePrimaryName {},
+    /// Creates `label.parent`, only the owner of the parent name can create subdomains
+    CreateSubdomain {
+        parent: String,
+        label: String,
+        owner: String,
+        #[serde(default)]
+        reclaimable: bool,
+    },
+    /// Returns a reclaimable subdomain to the owner of its parent name
+    ReclaimSubdomain {
+        name: String,
+    },
+    /// Sends collected fees to `to`, or the admin if unset
+    Withdraw {
+        amount: Vec<Coin>,
+        to: Option<String>,
+    },
+    /// Fields that are unset keep their current value
+    UpdateConfig {
+        #[serde(default)]
+        purchase_price: Option<PriceUpdate>,
+        #[serde(default)]
+        transfer_price: Option<PriceUpdate>,
+        #[serde(default)]
+        renewal_price: Option<PriceUpdate>,
+        /// An empty table removes all length prices
+        #[serde(default)]
+        length_prices: Option<Vec<LengthPrice>>,
+    },
+    /// Proposes a new admin, who has to accept before the role is handed over
+    TransferAdmin {
+        admin: String,
+    },
+    AcceptAdmin {},
+    /// Removes the admin for good, fees and prices can no longer be changed
+    RenounceAdmin {},
+    /// Bids on an available name, the first bid opens the auction
+    Bid {
+        name: String,
+    },
+    /// Registers the name to the highest bidder once the auction has ended
+    ClaimAuction {
+        name: String,
+    },
+    /// Offers a name for sale, replacing an existing listing
+    ListForSale {
+        name: String,
+        price: Coin,
+    },
+    CancelListing {
+        name: String,
+    },
+    /// Pays the listed price to the seller and moves the name to the buyer
+    Buy {
+        name: String,
+    },
+    /// Offers a name to `to`, it only moves once they accept.
+    /// Replaces an existing offer.
+    OfferTransfer {
+        name: String,
+        to: String,
+    },
+    /// Takes over an offered name, paying the transfer price
+    AcceptTransfer {
+        name: String,
+    },
+    CancelTransfer {
+        name: String,
+    },
+    /// cw721 transfer, the token id is the name. Unlike `Transfer` it does not
+    /// charge the transfer price, so generic cw721 wallets can move names.
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// Allows `spender` to transfer the name until the approval expires
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// Allows `operator` to update records and renew `name`, or all names of the
+    /// sender if no name is given. Transfers are only allowed with `can_transfer`.
+    ApproveOperator {
+        operator: String,
+        name: Option<String>,
+        #[serde(default)]
+        can_transfer: bool,
+        expires: Option<Expiration>,
+    },
+    RevokeOperator {
+        operator: String,
+        name: Option<String>,
+    },
+    /// Reserves names for the admin, or for `claimant` if set
+    Reserve {
+        pattern: ReservedPattern,
+        claimant: Option<String>,
+    },
+    Unreserve {
+        pattern: ReservedPattern,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReservedPattern {
+    /// Exactly this name
+    Name(String),
+    /// Every name starting with the prefix
+    Prefix(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceUpdate {
+    Set(Coin),
+    /// Removes the price, the action becomes free
+    Clear,
+}
+
+/// A single resolution record with its value
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Record {
+    /// Address the name resolves to instead of the owner
+    Address(String),
+    Url(String),
+    Avatar(String),
+    Email(String),
+    ContentHash(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKey {
+    Address,
+    Url,
+    Avatar,
+    Email,
+    ContentHash,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     // ResolveAddress returns the current address that the name resolves to
-    ResolveRecord { name: String },
+    ResolveRecord {
+        name: String,
+    },
     Config {},
+    // Records returns all resolution records set for the name
+    Records {
+        name: String,
+    },
+    // ReverseResolve returns the primary name of an address
+    ReverseResolve {
+        address: String,
+    },
+    // Price returns the cost of registering the name
+    Price {
+        name: String,
+    },
+    // Auction returns the running or unclaimed auction for the name
+    Auction {
+        name: String,
+    },
+    // Listings returns names currently for sale
+    Listings {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // TransferOffer returns the pending offer of a name
+    TransferOffer {
+        name: String,
+    },
+    // TransferOffers returns the pending offers to an address
+    TransferOffers {
+        recipient: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // NamesByOwner returns the active names held by an address
+    NamesByOwner {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // AllNames returns all active names
+    AllNames {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Operators returns the active operators of a name, including those of all owner names
+    Operators {
+        name: String,
+    },
+    // cw721 queries, token ids are names
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    NftInfo {
+        token_id: String,
+    },
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Reserved returns whether the name is reserved and who can claim it
+    Reserved {
+        name: String,
+    },
 }
 
 // We define a custom struct for each query response
@@ -28,3 +292,88 @@ pub enum QueryMsg {
 pub struct ResolveRecordResponse {
     pub address: Option<String>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
+pub struct RecordsResponse {
+    pub address: Option<String>,
+    pub url: Option<String>,
+    pub avatar: Option<String>,
+    pub email: Option<String>,
+    pub content_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReverseResolveResponse {
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceResponse {
+    pub price: Option<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionResponse {
+    pub auction: Option<Auction>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingInfo {
+    pub name: String,
+    pub seller: String,
+    pub price: Coin,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingsResponse {
+    pub listings: Vec<ListingInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferOfferInfo {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferOfferResponse {
+    pub offer: Option<TransferOfferInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferOffersResponse {
+    pub offers: Vec<TransferOfferInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NamesResponse {
+    pub names: Vec<String>,
+}
+
+/// Extension of the cw721 `NftInfo` response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NameMetadata {
+    pub expires: Option<Timestamp>,
+    pub records: RecordsResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReservedResponse {
+    pub reserved: bool,
+    pub claimant: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorInfo {
+    pub operator: String,
+    /// Approved for all names of the owner rather than this name only
+    pub all_names: bool,
+    pub can_transfer: bool,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorsResponse {
+    pub operators: Vec<OperatorInfo>,
+}
@@ -1,19 +1,90 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Coin, Storage};
+use cosmwasm_std::{Addr, BlockInfo, Coin, Empty, Storage, Timestamp, Uint128};
 use cosmwasm_storage::{
     bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
     Singleton,
 };
+use cw721::Expiration;
+
+use crate::idn::label_length;
 
 pub static NAME_RESOLVER_KEY: &[u8] = b"nameresolver";
 pub static CONFIG_KEY: &[u8] = b"config";
+pub static RECORDS_KEY: &[u8] = b"records";
+pub static REVERSE_KEY: &[u8] = b"reverse";
+pub static AUCTIONS_KEY: &[u8] = b"auctions";
+pub static ESCROW_KEY: &[u8] = b"escrow";
+pub static LISTINGS_KEY: &[u8] = b"listings";
+pub static OWNER_NAMES_KEY: &[u8] = b"owner_names";
+pub static OPERATORS_KEY: &[u8] = b"operators";
+pub static TRANSFER_OFFERS_KEY: &[u8] = b"transfer_offers";
+pub static RESERVED_NAMES_KEY: &[u8] = b"reserved_names";
+pub static RESERVED_PREFIXES_KEY: &[u8] = b"reserved_prefixes";
+
+/// Purchase price of names with exactly `length` characters
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LengthPrice {
+    pub length: u64,
+    pub price: Coin,
+}
+
+/// Names are sold by auction instead of first come first served
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionConfig {
+    /// Seconds an auction stays open after the first bid
+    pub duration: u64,
+    /// Amount a bid has to beat the current bid by, also sets the denom of bids
+    pub min_increment: Coin,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
+    /// Default purchase price for names without an entry in `length_prices`
     pub purchase_price: Option<Coin>,
     pub transfer_price: Option<Coin>,
+    #[serde(default)]
+    pub registration_period: Option<u64>,
+    #[serde(default)]
+    pub renewal_price: Option<Coin>,
+    #[serde(default)]
+    pub grace_period: u64,
+    /// Can withdraw fees and update prices. Nobody can once renounced.
+    #[serde(default)]
+    pub admin: Option<Addr>,
+    /// Proposed admin that still has to accept the role
+    #[serde(default)]
+    pub pending_admin: Option<Addr>,
+    /// Rejects payments above the price instead of refunding the excess
+    #[serde(default)]
+    pub strict_payment: bool,
+    #[serde(default)]
+    pub length_prices: Vec<LengthPrice>,
+    #[serde(default)]
+    pub auction: Option<AuctionConfig>,
+    /// Share of marketplace sales kept by the contract, in basis points
+    #[serde(default)]
+    pub sale_fee_bps: u64,
+    /// Accepts unicode names, which are stored in punycode form
+    #[serde(default)]
+    pub unicode_names: bool,
+}
+
+impl Config {
+    /// Purchase price of a name, looked up by its length
+    pub fn price_for(&self, name: &str) -> Option<Coin> {
+        let length = if self.unicode_names {
+            label_length(name)
+        } else {
+            name.len() as u64
+        };
+        self.length_prices
+            .iter()
+            .find(|tier| tier.length == length)
+            .map(|tier| tier.price.clone())
+            .or_else(|| self.purchase_price.clone())
+    }
 }
 
 pub fn config(storage: &mut dyn Storage) -> Singleton<Config> {
@@ -27,6 +98,59 @@ pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<Config> {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct NameRecord {
     pub owner: Addr,
+    /// Records registered without a registration period never expire
+    #[serde(default)]
+    pub expires: Option<Timestamp>,
+    /// Subdomains created as reclaimable can be taken back by the parent owner
+    #[serde(default)]
+    pub reclaimable: bool,
+    /// Spenders approved through cw721 to transfer the name
+    #[serde(default)]
+    pub approvals: Vec<Approval>,
+    /// Operators approved by the owner to manage this name
+    #[serde(default)]
+    pub operators: Vec<Operator>,
+    /// Counts how often the name was registered again, subdomains belong to one registration
+    #[serde(default)]
+    pub generation: u64,
+    /// Generation of the parent name this subdomain was created under
+    #[serde(default)]
+    pub parent_generation: u64,
+    /// Set for subdomains. Top level names registered before subdomains
+    /// existed may contain a '.' as well.
+    #[serde(default)]
+    pub subdomain: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub spender: Addr,
+    pub expires: Expiration,
+}
+
+/// Operators can update records and renew names, but only transfer them if allowed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Operator {
+    pub operator: Addr,
+    pub can_transfer: bool,
+    pub expires: Expiration,
+}
+
+impl NameRecord {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        match self.expires {
+            Some(expires) => block.time >= expires,
+            None => false,
+        }
+    }
+
+    /// An expired name can be registered by anyone once the grace period is over
+    pub fn is_available(&self, block: &BlockInfo, grace_period: u64) -> bool {
+        match self.expires {
+            Some(expires) => block.time >= expires.plus_seconds(grace_period),
+            None => false,
+        }
+    }
 }
 
 pub fn resolver(storage: &mut dyn Storage) -> Bucket<NameRecord> {
@@ -36,3 +160,141 @@ pub fn resolver(storage: &mut dyn Storage) -> Bucket<NameRecord> {
 pub fn resolver_read(storage: &dyn Storage) -> ReadonlyBucket<NameRecord> {
     bucket_read(storage, NAME_RESOLVER_KEY)
 }
+
+/// Resolution records set by the owner of a name
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
+pub struct Records {
+    /// Address the name resolves to, defaults to the owner if unset
+    pub address: Option<Addr>,
+    pub url: Option<String>,
+    pub avatar: Option<String>,
+    pub email: Option<String>,
+    pub content_hash: Option<String>,
+}
+
+impl Records {
+    pub fn is_empty(&self) -> bool {
+        self == &Records::default()
+    }
+}
+
+pub fn records(storage: &mut dyn Storage) -> Bucket<Records> {
+    bucket(storage, RECORDS_KEY)
+}
+
+pub fn records_read(storage: &dyn Storage) -> ReadonlyBucket<Records> {
+    bucket_read(storage, RECORDS_KEY)
+}
+
+/// Primary name of an address, keyed by the address
+pub fn reverse(storage: &mut dyn Storage) -> Bucket<String> {
+    bucket(storage, REVERSE_KEY)
+}
+
+pub fn reverse_read(storage: &dyn Storage) -> ReadonlyBucket<String> {
+    bucket_read(storage, REVERSE_KEY)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Auction {
+    pub bidder: Addr,
+    pub bid: Coin,
+    pub ends: Timestamp,
+}
+
+impl Auction {
+    pub fn is_ended(&self, block: &BlockInfo) -> bool {
+        block.time >= self.ends
+    }
+}
+
+pub fn auctions(storage: &mut dyn Storage) -> Bucket<Auction> {
+    bucket(storage, AUCTIONS_KEY)
+}
+
+pub fn auctions_read(storage: &dyn Storage) -> ReadonlyBucket<Auction> {
+    bucket_read(storage, AUCTIONS_KEY)
+}
+
+/// Sum of the bids held for running auctions, keyed by denom
+pub fn escrow(storage: &mut dyn Storage) -> Bucket<Uint128> {
+    bucket(storage, ESCROW_KEY)
+}
+
+pub fn escrow_read(storage: &dyn Storage) -> ReadonlyBucket<Uint128> {
+    bucket_read(storage, ESCROW_KEY)
+}
+
+/// A name offered for sale by its owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Listing {
+    pub seller: Addr,
+    pub price: Coin,
+}
+
+pub fn listings(storage: &mut dyn Storage) -> Bucket<Listing> {
+    bucket(storage, LISTINGS_KEY)
+}
+
+pub fn listings_read(storage: &dyn Storage) -> ReadonlyBucket<Listing> {
+    bucket_read(storage, LISTINGS_KEY)
+}
+
+/// A name offered by its owner, it moves once the recipient accepts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferOffer {
+    pub from: Addr,
+    pub to: Addr,
+}
+
+pub fn transfer_offers(storage: &mut dyn Storage) -> Bucket<TransferOffer> {
+    bucket(storage, TRANSFER_OFFERS_KEY)
+}
+
+pub fn transfer_offers_read(storage: &dyn Storage) -> ReadonlyBucket<TransferOffer> {
+    bucket_read(storage, TRANSFER_OFFERS_KEY)
+}
+
+/// Operators approved by an owner for all of their names, keyed by operator
+pub fn owner_operators<'a>(storage: &'a mut dyn Storage, owner: &Addr) -> Bucket<'a, Operator> {
+    Bucket::multilevel(storage, &[OPERATORS_KEY, owner.as_bytes()])
+}
+
+pub fn owner_operators_read<'a>(
+    storage: &'a dyn Storage,
+    owner: &Addr,
+) -> ReadonlyBucket<'a, Operator> {
+    ReadonlyBucket::multilevel(storage, &[OPERATORS_KEY, owner.as_bytes()])
+}
+
+/// Index of the names held by an owner, keyed by name
+pub fn owner_names<'a>(storage: &'a mut dyn Storage, owner: &Addr) -> Bucket<'a, Empty> {
+    Bucket::multilevel(storage, &[OWNER_NAMES_KEY, owner.as_bytes()])
+}
+
+pub fn owner_names_read<'a>(storage: &'a dyn Storage, owner: &Addr) -> ReadonlyBucket<'a, Empty> {
+    ReadonlyBucket::multilevel(storage, &[OWNER_NAMES_KEY, owner.as_bytes()])
+}
+
+/// Reserved names can only be registered by the admin or the claimant
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Reservation {
+    pub claimant: Option<Addr>,
+}
+
+pub fn reserved_names(storage: &mut dyn Storage) -> Bucket<Reservation> {
+    bucket(storage, RESERVED_NAMES_KEY)
+}
+
+pub fn reserved_names_read(storage: &dyn Storage) -> ReadonlyBucket<Reservation> {
+    bucket_read(storage, RESERVED_NAMES_KEY)
+}
+
+/// Reservations covering every name starting with the key
+pub fn reserved_prefixes(storage: &mut dyn Storage) -> Bucket<Reservation> {
+    bucket(storage, RESERVED_PREFIXES_KEY)
+}
+
+pub fn reserved_prefixes_read(storage: &dyn Storage) -> ReadonlyBucket<Reservation> {
+    bucket_read(storage, RESERVED_PREFIXES_KEY)
+}
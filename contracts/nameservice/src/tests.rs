@@ -1,12 +1,26 @@
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coin, coins, from_binary, Coin, Deps, DepsMut};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{
+        coin, coins, from_binary, from_slice, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env,
+        Response,
+    };
+
+    use cw721::{
+        Approval as Cw721Approval, Expiration, NftInfoResponse, OwnerOfResponse, TokensResponse,
+    };
 
     use crate::contract::{execute, instantiate, query};
-    use crate::error::ContractError;
-    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ResolveRecordResponse};
-    use crate::state::Config;
+    use crate::error::{ContractError, InvalidCharacterReason};
+    use crate::msg::{
+        AuctionResponse, ExecuteMsg, InstantiateMsg, ListingsResponse, NameMetadata, NamesResponse,
+        OperatorInfo, OperatorsResponse, PriceResponse, PriceUpdate, QueryMsg, Record, RecordKey,
+        RecordsResponse, ReservedPattern, ReservedResponse, ResolveRecordResponse,
+        ReverseResolveResponse, TransferOfferInfo, TransferOfferResponse, TransferOffersResponse,
+    };
+    use crate::state::{resolver, AuctionConfig, Config, LengthPrice};
 
     fn assert_name_owner(deps: Deps, name: &str, owner: &str) {
         let res = query(
@@ -32,6 +46,15 @@ mod tests {
         let msg = InstantiateMsg {
             purchase_price: Some(purchase_price),
             transfer_price: Some(transfer_price),
+            registration_period: None,
+            renewal_price: None,
+            grace_period: 0,
+            admin: None,
+            strict_payment: false,
+            length_prices: vec![],
+            auction: None,
+            sale_fee_bps: 0,
+            unicode_names: false,
         };
 
         let info = mock_info("creator", &coins(2, "token"));
@@ -43,6 +66,15 @@ mod tests {
         let msg = InstantiateMsg {
             purchase_price: None,
             transfer_price: None,
+            registration_period: None,
+            renewal_price: None,
+            grace_period: 0,
+            admin: None,
+            strict_payment: false,
+            length_prices: vec![],
+            auction: None,
+            sale_fee_bps: 0,
+            unicode_names: false,
         };
 
         let info = mock_info("creator", &coins(2, "token"));
@@ -50,6 +82,32 @@ mod tests {
             .expect("contract successfully handles InstantiateMsg");
     }
 
+    fn mock_init_with_expiration(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            purchase_price: None,
+            transfer_price: None,
+            registration_period: Some(1000),
+            renewal_price: Some(coin(3, "token")),
+            grace_period: 100,
+            admin: None,
+            strict_payment: false,
+            length_prices: vec![],
+            auction: None,
+            sale_fee_bps: 0,
+            unicode_names: false,
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    fn mock_env_at(seconds_from_now: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds_from_now);
+        env
+    }
+
     fn mock_alice_registers_name(deps: DepsMut, sent: &[Coin]) {
         // alice can register an available name
         let info = mock_info("alice_key", sent);
@@ -62,7 +120,7 @@ mod tests {
 
     #[test]
     fn proper_init_no_fees() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
 
         mock_init_no_price(deps.as_mut());
 
@@ -71,13 +129,23 @@ mod tests {
             Config {
                 purchase_price: None,
                 transfer_price: None,
+                registration_period: None,
+                renewal_price: None,
+                grace_period: 0,
+                admin: Some(Addr::unchecked("creator")),
+                pending_admin: None,
+                strict_payment: false,
+                length_prices: vec![],
+                auction: None,
+                sale_fee_bps: 0,
+                unicode_names: false,
             },
         );
     }
 
     #[test]
     fn proper_init_with_fees() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
 
         mock_init_with_price(deps.as_mut(), coin(3, "token"), coin(4, "token"));
 
@@ -86,13 +154,23 @@ mod tests {
             Config {
                 purchase_price: Some(coin(3, "token")),
                 transfer_price: Some(coin(4, "token")),
+                registration_period: None,
+                renewal_price: None,
+                grace_period: 0,
+                admin: Some(Addr::unchecked("creator")),
+                pending_admin: None,
+                strict_payment: false,
+                length_prices: vec![],
+                auction: None,
+                sale_fee_bps: 0,
+                unicode_names: false,
             },
         );
     }
 
     #[test]
     fn register_available_name_and_query_works() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_no_price(deps.as_mut());
         mock_alice_registers_name(deps.as_mut(), &[]);
 
@@ -102,7 +180,7 @@ mod tests {
 
     #[test]
     fn register_available_name_and_query_works_with_fees() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
         mock_alice_registers_name(deps.as_mut(), &coins(2, "token"));
 
@@ -122,7 +200,7 @@ mod tests {
 
     #[test]
     fn fails_on_register_already_taken_name() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_no_price(deps.as_mut());
         mock_alice_registers_name(deps.as_mut(), &[]);
 
@@ -154,7 +232,7 @@ mod tests {
 
     #[test]
     fn register_available_name_fails_with_invalid_name() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_no_price(deps.as_mut());
         let info = mock_info("bob_key", &coins(2, "token"));
 
@@ -184,23 +262,33 @@ mod tests {
         };
         match execute(deps.as_mut(), mock_env(), info.clone(), msg) {
             Ok(_) => panic!("Must return error"),
-            Err(ContractError::InvalidCharacter { c }) => assert_eq!(c, 'L'),
+            Err(ContractError::InvalidCharacter { c, .. }) => assert_eq!(c, 'L'),
             Err(_) => panic!("Unknown error"),
         }
         // ... or spaces
         let msg = ExecuteMsg::Register {
             name: "two words".to_string(),
         };
-        match execute(deps.as_mut(), mock_env(), info, msg) {
+        match execute(deps.as_mut(), mock_env(), info.clone(), msg) {
             Ok(_) => panic!("Must return error"),
             Err(ContractError::InvalidCharacter { .. }) => {}
             Err(_) => panic!("Unknown error"),
         }
+
+        // ... or dots, which are reserved for subdomains
+        let msg = ExecuteMsg::Register {
+            name: "pay.alice".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InvalidCharacter { c, .. }) => assert_eq!(c, '.'),
+            Err(_) => panic!("Unknown error"),
+        }
     }
 
     #[test]
     fn fails_on_register_insufficient_fees() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
 
         // anyone can register an available name with sufficient fees
@@ -220,7 +308,7 @@ mod tests {
 
     #[test]
     fn fails_on_register_wrong_fee_denom() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
 
         // anyone can register an available name with sufficient fees
@@ -240,7 +328,7 @@ mod tests {
 
     #[test]
     fn transfer_works() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_no_price(deps.as_mut());
         mock_alice_registers_name(deps.as_mut(), &[]);
 
@@ -259,7 +347,7 @@ mod tests {
 
     #[test]
     fn transfer_works_with_fees() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
         mock_alice_registers_name(deps.as_mut(), &coins(2, "token"));
 
@@ -278,7 +366,7 @@ mod tests {
 
     #[test]
     fn fails_on_transfer_non_existent() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_no_price(deps.as_mut());
         mock_alice_registers_name(deps.as_mut(), &[]);
 
@@ -303,7 +391,7 @@ mod tests {
 
     #[test]
     fn fails_on_transfer_from_nonowner() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_no_price(deps.as_mut());
         mock_alice_registers_name(deps.as_mut(), &[]);
 
@@ -328,7 +416,7 @@ mod tests {
 
     #[test]
     fn fails_on_transfer_insufficient_fees() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
         mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(5, "token"));
         mock_alice_registers_name(deps.as_mut(), &coins(2, "token"));
 
@@ -353,7 +441,7 @@ mod tests {
 
     #[test]
     fn returns_empty_on_query_unregistered_name() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies();
 
         mock_init_no_price(deps.as_mut());
 
@@ -369,4 +457,2592 @@ mod tests {
         let value: ResolveRecordResponse = from_binary(&res).unwrap();
         assert_eq!(None, value.address);
     }
+
+    #[test]
+    fn expired_name_stops_resolving() {
+        let mut deps = mock_dependencies();
+        mock_init_with_expiration(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        assert_name_owner(deps.as_ref(), "alice", "alice_key");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env_at(1000),
+            QueryMsg::ResolveRecord {
+                name: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ResolveRecordResponse = from_binary(&res).unwrap();
+        assert_eq!(None, value.address);
+
+        // expired names cannot be transferred
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alice".to_string(),
+            to: "bob_key".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env_at(1000), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NameExpired { name }) => assert_eq!(name, "alice"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn expired_name_can_be_registered_after_grace_period() {
+        let mut deps = mock_dependencies();
+        mock_init_with_expiration(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let msg = ExecuteMsg::Register {
+            name: "alice".to_string(),
+        };
+
+        // still within the grace period
+        let info = mock_info("bob_key", &[]);
+        match execute(deps.as_mut(), mock_env_at(1099), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NameTaken { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("bob_key", &[]);
+        let _res = execute(deps.as_mut(), mock_env_at(1100), info, msg)
+            .expect("contract successfully handles Register message");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env_at(1100),
+            QueryMsg::ResolveRecord {
+                name: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ResolveRecordResponse = from_binary(&res).unwrap();
+        assert_eq!(Some("bob_key".to_string()), value.address);
+    }
+
+    #[test]
+    fn renew_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_expiration(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let msg = ExecuteMsg::Renew {
+            name: "alice".to_string(),
+        };
+
+        // renewal fee is required
+        let info = mock_info("alice_key", &coins(2, "token"));
+        match execute(deps.as_mut(), mock_env_at(500), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // only the owner can renew
+        let info = mock_info("bob_key", &coins(3, "token"));
+        match execute(deps.as_mut(), mock_env_at(500), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // renewing during the grace period extends the previous expiration
+        let info = mock_info("alice_key", &coins(3, "token"));
+        let _res = execute(deps.as_mut(), mock_env_at(1050), info, msg)
+            .expect("contract successfully handles Renew message");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env_at(1999),
+            QueryMsg::ResolveRecord {
+                name: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ResolveRecordResponse = from_binary(&res).unwrap();
+        assert_eq!(Some("alice_key".to_string()), value.address);
+    }
+
+    #[test]
+    fn fails_on_renew_after_grace_period() {
+        let mut deps = mock_dependencies();
+        mock_init_with_expiration(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let info = mock_info("alice_key", &coins(3, "token"));
+        let msg = ExecuteMsg::Renew {
+            name: "alice".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env_at(1100), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    fn query_records(deps: Deps, name: &str) -> RecordsResponse {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::Records {
+                name: name.to_string(),
+            },
+        )
+        .unwrap();
+        from_binary(&res).unwrap()
+    }
+
+    #[test]
+    fn set_and_remove_records_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        assert_eq!(
+            RecordsResponse::default(),
+            query_records(deps.as_ref(), "alice")
+        );
+
+        let records = vec![
+            Record::Address("alice_wallet".to_string()),
+            Record::Url("https://alice.example".to_string()),
+            Record::Email("alice@example.com".to_string()),
+        ];
+        for record in records {
+            let info = mock_info("alice_key", &[]);
+            let msg = ExecuteMsg::SetRecord {
+                name: "alice".to_string(),
+                record,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg)
+                .expect("contract successfully handles SetRecord message");
+        }
+
+        assert_eq!(
+            RecordsResponse {
+                address: Some("alice_wallet".to_string()),
+                url: Some("https://alice.example".to_string()),
+                avatar: None,
+                email: Some("alice@example.com".to_string()),
+                content_hash: None,
+            },
+            query_records(deps.as_ref(), "alice")
+        );
+        // the name resolves to the address record instead of the owner
+        assert_name_owner(deps.as_ref(), "alice", "alice_wallet");
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::RemoveRecord {
+            name: "alice".to_string(),
+            key: RecordKey::Address,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles RemoveRecord message");
+
+        assert_name_owner(deps.as_ref(), "alice", "alice_key");
+        let value = query_records(deps.as_ref(), "alice");
+        assert_eq!(None, value.address);
+        assert_eq!(Some("https://alice.example".to_string()), value.url);
+    }
+
+    #[test]
+    fn fails_on_set_record_from_nonowner() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::SetRecord {
+            name: "alice".to_string(),
+            record: Record::Address("bob_key".to_string()),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        assert_name_owner(deps.as_ref(), "alice", "alice_key");
+    }
+
+    #[test]
+    fn fails_on_set_record_too_long() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::SetRecord {
+            name: "alice".to_string(),
+            record: Record::ContentHash("a".repeat(129)),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::RecordTooLong {
+                key,
+                length,
+                max_length,
+            }) => {
+                assert_eq!(key, "content_hash");
+                assert_eq!(length, 129);
+                assert_eq!(max_length, 128);
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn transfer_clears_records() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::SetRecord {
+            name: "alice".to_string(),
+            record: Record::Address("alice_wallet".to_string()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles SetRecord message");
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alice".to_string(),
+            to: "bob_key".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Transfer message");
+
+        assert_name_owner(deps.as_ref(), "alice", "bob_key");
+        assert_eq!(
+            RecordsResponse::default(),
+            query_records(deps.as_ref(), "alice")
+        );
+    }
+
+    fn assert_primary_name(deps: Deps, env: Env, address: &str, expected: Option<&str>) {
+        let res = query(
+            deps,
+            env,
+            QueryMsg::ReverseResolve {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+        let value: ReverseResolveResponse = from_binary(&res).unwrap();
+        assert_eq!(expected.map(String::from), value.name);
+    }
+
+    fn mock_alice_sets_primary_name(deps: DepsMut) {
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::SetPrimaryName {
+            name: "alice".to_string(),
+        };
+        let _res = execute(deps, mock_env(), info, msg)
+            .expect("contract successfully handles SetPrimaryName message");
+    }
+
+    #[test]
+    fn set_and_remove_primary_name_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        assert_primary_name(deps.as_ref(), mock_env(), "alice_key", None);
+
+        mock_alice_sets_primary_name(deps.as_mut());
+        assert_primary_name(deps.as_ref(), mock_env(), "alice_key", Some("alice"));
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::RemovePrimaryName {};
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles RemovePrimaryName message");
+        assert_primary_name(deps.as_ref(), mock_env(), "alice_key", None);
+    }
+
+    #[test]
+    fn fails_on_set_primary_name_from_nonowner() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::SetPrimaryName {
+            name: "alice".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        assert_primary_name(deps.as_ref(), mock_env(), "bob_key", None);
+    }
+
+    #[test]
+    fn transfer_clears_primary_name() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_sets_primary_name(deps.as_mut());
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alice".to_string(),
+            to: "bob_key".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Transfer message");
+
+        assert_primary_name(deps.as_ref(), mock_env(), "alice_key", None);
+        assert_primary_name(deps.as_ref(), mock_env(), "bob_key", None);
+    }
+
+    #[test]
+    fn expired_primary_name_is_not_reported() {
+        let mut deps = mock_dependencies();
+        mock_init_with_expiration(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_sets_primary_name(deps.as_mut());
+
+        assert_primary_name(deps.as_ref(), mock_env_at(999), "alice_key", Some("alice"));
+        assert_primary_name(deps.as_ref(), mock_env_at(1000), "alice_key", None);
+    }
+
+    fn mock_alice_creates_subdomain(deps: DepsMut, label: &str, owner: &str, reclaimable: bool) {
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::CreateSubdomain {
+            parent: "alice".to_string(),
+            label: label.to_string(),
+            owner: owner.to_string(),
+            reclaimable,
+        };
+        let _res = execute(deps, mock_env(), info, msg)
+            .expect("contract successfully handles CreateSubdomain message");
+    }
+
+    #[test]
+    fn create_subdomain_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_creates_subdomain(deps.as_mut(), "pay", "bob_key", false);
+
+        assert_name_owner(deps.as_ref(), "pay.alice", "bob_key");
+
+        // the subdomain owner manages its records and subdomains
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::SetRecord {
+            name: "pay.alice".to_string(),
+            record: Record::Address("bob_wallet".to_string()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles SetRecord message");
+        assert_name_owner(deps.as_ref(), "pay.alice", "bob_wallet");
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::CreateSubdomain {
+            parent: "pay.alice".to_string(),
+            label: "eur".to_string(),
+            owner: "bob_key".to_string(),
+            reclaimable: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles CreateSubdomain message");
+        assert_name_owner(deps.as_ref(), "eur.pay.alice", "bob_key");
+
+        // the parent name is unaffected
+        assert_name_owner(deps.as_ref(), "alice", "alice_key");
+    }
+
+    #[test]
+    fn fails_on_create_subdomain_invalid() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_creates_subdomain(deps.as_mut(), "pay", "bob_key", false);
+
+        // only the parent owner can create subdomains
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::CreateSubdomain {
+            parent: "alice".to_string(),
+            label: "shop".to_string(),
+            owner: "bob_key".to_string(),
+            reclaimable: false,
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // labels are validated like names
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::CreateSubdomain {
+            parent: "alice".to_string(),
+            label: "a.b".to_string(),
+            owner: "bob_key".to_string(),
+            reclaimable: false,
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InvalidCharacter { c, .. }) => assert_eq!(c, '.'),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // existing subdomains cannot be overwritten
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::CreateSubdomain {
+            parent: "alice".to_string(),
+            label: "pay".to_string(),
+            owner: "alice_key".to_string(),
+            reclaimable: false,
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NameTaken { name }) => assert_eq!(name, "pay.alice"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn fails_on_create_subdomain_too_long() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        // labels can not nest beyond the length of a full name
+        let label = "a".repeat(60);
+        let mut parent = "alice".to_string();
+        for _ in 0..4 {
+            let info = mock_info("alice_key", &[]);
+            let msg = ExecuteMsg::CreateSubdomain {
+                parent: parent.clone(),
+                label: label.clone(),
+                owner: "alice_key".to_string(),
+                reclaimable: false,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg)
+                .expect("contract successfully handles CreateSubdomain message");
+            parent = format!("{}.{}", label, parent);
+        }
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::CreateSubdomain {
+            parent,
+            label,
+            owner: "alice_key".to_string(),
+            reclaimable: false,
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NameTooLong { length, max_length }) => {
+                assert_eq!(310, length);
+                assert_eq!(253, max_length);
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn dotted_top_level_name_keeps_working() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        // names with a '.' could be registered before subdomains existed
+        let legacy = from_slice(br#"{"owner":"alice_key","expires":null}"#).unwrap();
+        resolver(&mut deps.storage)
+            .save(b"alice.bob", &legacy)
+            .unwrap();
+        assert_name_owner(deps.as_ref(), "alice.bob", "alice_key");
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alice.bob".to_string(),
+            to: "carol_key".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Transfer message");
+        assert_name_owner(deps.as_ref(), "alice.bob", "carol_key");
+
+        // registering the last label does not take over the old name
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Register {
+            name: "bob".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Register message");
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::ReclaimSubdomain {
+            name: "alice.bob".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NotSubdomain { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        assert_name_owner(deps.as_ref(), "alice.bob", "carol_key");
+    }
+
+    #[test]
+    fn parent_transfer_keeps_subdomain_owner() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_creates_subdomain(deps.as_mut(), "pay", "alice_key", false);
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alice".to_string(),
+            to: "bob_key".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Transfer message");
+
+        assert_name_owner(deps.as_ref(), "alice", "bob_key");
+        assert_name_owner(deps.as_ref(), "pay.alice", "alice_key");
+    }
+
+    #[test]
+    fn reclaim_subdomain_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_creates_subdomain(deps.as_mut(), "pay", "bob_key", true);
+        mock_alice_creates_subdomain(deps.as_mut(), "shop", "bob_key", false);
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::ReclaimSubdomain {
+            name: "pay.alice".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles ReclaimSubdomain message");
+        assert_name_owner(deps.as_ref(), "pay.alice", "alice_key");
+
+        // subdomains created without the option stay with their owner
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::ReclaimSubdomain {
+            name: "shop.alice".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        assert_name_owner(deps.as_ref(), "shop.alice", "bob_key");
+    }
+
+    #[test]
+    fn subdomain_expires_with_parent() {
+        let mut deps = mock_dependencies();
+        mock_init_with_expiration(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_creates_subdomain(deps.as_mut(), "pay", "bob_key", false);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env_at(1000),
+            QueryMsg::ResolveRecord {
+                name: "pay.alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ResolveRecordResponse = from_binary(&res).unwrap();
+        assert_eq!(None, value.address);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "pay.alice".to_string(),
+            to: "alice_key".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env_at(1000), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NameExpired { name }) => assert_eq!(name, "pay.alice"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // a new owner of the parent does not inherit the subdomains of the previous one
+        let info = mock_info("carol_key", &[]);
+        let msg = ExecuteMsg::Register {
+            name: "alice".to_string(),
+        };
+        execute(deps.as_mut(), mock_env_at(1200), info.clone(), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env_at(1200),
+            QueryMsg::ResolveRecord {
+                name: "pay.alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ResolveRecordResponse = from_binary(&res).unwrap();
+        assert_eq!(None, value.address);
+
+        // and can create them again
+        let msg = ExecuteMsg::CreateSubdomain {
+            parent: "alice".to_string(),
+            label: "pay".to_string(),
+            owner: "dave_key".to_string(),
+            reclaimable: false,
+        };
+        execute(deps.as_mut(), mock_env_at(1200), info, msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env_at(1200),
+            QueryMsg::ResolveRecord {
+                name: "pay.alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ResolveRecordResponse = from_binary(&res).unwrap();
+        assert_eq!(Some("dave_key".to_string()), value.address);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env_at(1200),
+            QueryMsg::NamesByOwner {
+                owner: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: NamesResponse = from_binary(&res).unwrap();
+        assert!(value.names.is_empty());
+    }
+
+    #[test]
+    fn withdraw_works() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+        mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
+        mock_alice_registers_name(deps.as_mut(), &coins(2, "token"));
+
+        // only the admin can withdraw
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(2, "token"),
+            to: None,
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("creator", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: coins(2, "token"),
+            })
+        );
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(2, "token"),
+            to: Some("treasury".to_string()),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(2, "token"),
+            })
+        );
+    }
+
+    #[test]
+    fn fails_on_withdraw_zero_amount() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+        mock_init_no_price(deps.as_mut());
+
+        for amount in [
+            vec![],
+            vec![coin(0, "token")],
+            vec![coin(2, "token"), coin(0, "other")],
+        ] {
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::Withdraw { amount, to: None };
+            match execute(deps.as_mut(), mock_env(), info, msg) {
+                Ok(_) => panic!("Must return error"),
+                Err(ContractError::InvalidZeroAmount {}) => {}
+                Err(e) => panic!("Unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn update_config_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        let msg = ExecuteMsg::UpdateConfig {
+            purchase_price: Some(PriceUpdate::Set(coin(5, "token"))),
+            transfer_price: None,
+            renewal_price: Some(PriceUpdate::Set(coin(1, "token"))),
+            length_prices: None,
+        };
+
+        let info = mock_info("alice_key", &[]);
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles UpdateConfig message");
+
+        assert_config_state(
+            deps.as_ref(),
+            Config {
+                purchase_price: Some(coin(5, "token")),
+                transfer_price: None,
+                registration_period: None,
+                renewal_price: Some(coin(1, "token")),
+                grace_period: 0,
+                admin: Some(Addr::unchecked("creator")),
+                pending_admin: None,
+                strict_payment: false,
+                length_prices: vec![],
+                auction: None,
+                sale_fee_bps: 0,
+                unicode_names: false,
+            },
+        );
+
+        // the new price applies to registrations
+        let info = mock_info("alice_key", &coins(2, "token"));
+        let msg = ExecuteMsg::Register {
+            name: "alice".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn transfer_and_renounce_admin_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::TransferAdmin {
+            admin: "new_admin".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles TransferAdmin message");
+
+        // the role only changes hands once accepted by the proposed admin
+        let info = mock_info("alice_key", &[]);
+        match execute(deps.as_mut(), mock_env(), info, ExecuteMsg::AcceptAdmin {}) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("new_admin", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::AcceptAdmin {})
+            .expect("contract successfully handles AcceptAdmin message");
+
+        let info = mock_info("creator", &[]);
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::RenounceAdmin {},
+        ) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("new_admin", &[]);
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::RenounceAdmin {},
+        )
+        .expect("contract successfully handles RenounceAdmin message");
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let value: Config = from_binary(&res).unwrap();
+        assert_eq!(None, value.admin);
+        assert_eq!(None, value.pending_admin);
+    }
+
+    #[test]
+    fn register_refunds_excess_funds() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
+
+        let info = mock_info("alice_key", &[coin(5, "token"), coin(1, "earth")]);
+        let msg = ExecuteMsg::Register {
+            name: "alice".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Register message");
+
+        assert_eq!(1, res.messages.len());
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice_key".to_string(),
+                amount: vec![coin(3, "token"), coin(1, "earth")],
+            })
+        );
+        assert_name_owner(deps.as_ref(), "alice", "alice_key");
+    }
+
+    #[test]
+    fn transfer_refunds_excess_funds() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
+        mock_alice_registers_name(deps.as_mut(), &coins(2, "token"));
+
+        let info = mock_info("alice_key", &coins(3, "token"));
+        let msg = ExecuteMsg::Transfer {
+            name: "alice".to_string(),
+            to: "bob_key".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Transfer message");
+
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice_key".to_string(),
+                amount: coins(1, "token"),
+            })
+        );
+    }
+
+    #[test]
+    fn exact_payment_sends_no_refund() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
+
+        let info = mock_info("alice_key", &coins(2, "token"));
+        let msg = ExecuteMsg::Register {
+            name: "alice".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Register message");
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn fails_on_excess_funds_in_strict_mode() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            purchase_price: Some(coin(2, "token")),
+            transfer_price: None,
+            registration_period: None,
+            renewal_price: None,
+            grace_period: 0,
+            admin: None,
+            strict_payment: true,
+            length_prices: vec![],
+            auction: None,
+            sale_fee_bps: 0,
+            unicode_names: false,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+
+        let msg = ExecuteMsg::Register {
+            name: "alice".to_string(),
+        };
+        let info = mock_info("alice_key", &coins(3, "token"));
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::ExcessFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("alice_key", &[coin(2, "token"), coin(1, "earth")]);
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::ExcessFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("alice_key", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Register message");
+        assert_name_owner(deps.as_ref(), "alice", "alice_key");
+    }
+
+    fn mock_init_with_length_prices(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            purchase_price: Some(coin(1, "token")),
+            transfer_price: None,
+            registration_period: None,
+            renewal_price: None,
+            grace_period: 0,
+            admin: None,
+            strict_payment: false,
+            length_prices: vec![
+                LengthPrice {
+                    length: 3,
+                    price: coin(100, "token"),
+                },
+                LengthPrice {
+                    length: 4,
+                    price: coin(10, "token"),
+                },
+            ],
+            auction: None,
+            sale_fee_bps: 0,
+            unicode_names: false,
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    fn assert_price(deps: Deps, name: &str, expected: Option<Coin>) {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::Price {
+                name: name.to_string(),
+            },
+        )
+        .unwrap();
+        let value: PriceResponse = from_binary(&res).unwrap();
+        assert_eq!(expected, value.price);
+    }
+
+    #[test]
+    fn price_query_uses_length_prices() {
+        let mut deps = mock_dependencies();
+        mock_init_with_length_prices(deps.as_mut());
+
+        assert_price(deps.as_ref(), "bob", Some(coin(100, "token")));
+        assert_price(deps.as_ref(), "carl", Some(coin(10, "token")));
+        assert_price(deps.as_ref(), "alice", Some(coin(1, "token")));
+    }
+
+    #[test]
+    fn register_charges_by_length() {
+        let mut deps = mock_dependencies();
+        mock_init_with_length_prices(deps.as_mut());
+
+        let info = mock_info("bob_key", &coins(10, "token"));
+        let msg = ExecuteMsg::Register {
+            name: "bob".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("bob_key", &coins(100, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Register message");
+        assert_name_owner(deps.as_ref(), "bob", "bob_key");
+
+        // names without a tier pay the default price
+        mock_alice_registers_name(deps.as_mut(), &coins(1, "token"));
+    }
+
+    #[test]
+    fn update_config_keeps_length_prices_if_unset() {
+        let mut deps = mock_dependencies();
+        mock_init_with_length_prices(deps.as_mut());
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            purchase_price: Some(PriceUpdate::Set(coin(2, "token"))),
+            transfer_price: None,
+            renewal_price: None,
+            length_prices: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles UpdateConfig message");
+
+        assert_price(deps.as_ref(), "bob", Some(coin(100, "token")));
+        assert_price(deps.as_ref(), "alice", Some(coin(2, "token")));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            purchase_price: Some(PriceUpdate::Set(coin(2, "token"))),
+            transfer_price: None,
+            renewal_price: None,
+            length_prices: Some(vec![]),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles UpdateConfig message");
+
+        assert_price(deps.as_ref(), "bob", Some(coin(2, "token")));
+    }
+
+    #[test]
+    fn update_config_keeps_unset_prices() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(3, "token"));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            purchase_price: None,
+            transfer_price: None,
+            renewal_price: Some(PriceUpdate::Set(coin(1, "token"))),
+            length_prices: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles UpdateConfig message");
+
+        let expected = Config {
+            purchase_price: Some(coin(2, "token")),
+            transfer_price: Some(coin(3, "token")),
+            registration_period: None,
+            renewal_price: Some(coin(1, "token")),
+            grace_period: 0,
+            admin: Some(Addr::unchecked("creator")),
+            pending_admin: None,
+            strict_payment: false,
+            length_prices: vec![],
+            auction: None,
+            sale_fee_bps: 0,
+            unicode_names: false,
+        };
+        assert_config_state(deps.as_ref(), expected.clone());
+
+        // a price is only removed when cleared explicitly
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            purchase_price: None,
+            transfer_price: Some(PriceUpdate::Clear),
+            renewal_price: None,
+            length_prices: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles UpdateConfig message");
+
+        assert_config_state(
+            deps.as_ref(),
+            Config {
+                transfer_price: None,
+                ..expected
+            },
+        );
+    }
+
+    fn mock_init_with_auction(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            purchase_price: Some(coin(10, "token")),
+            transfer_price: None,
+            registration_period: None,
+            renewal_price: None,
+            grace_period: 0,
+            admin: None,
+            strict_payment: false,
+            length_prices: vec![],
+            auction: Some(AuctionConfig {
+                duration: 100,
+                min_increment: coin(5, "token"),
+            }),
+            sale_fee_bps: 0,
+            unicode_names: false,
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    fn bid(deps: DepsMut, env: Env, bidder: &str, amount: u128) -> Result<Response, ContractError> {
+        let info = mock_info(bidder, &coins(amount, "token"));
+        let msg = ExecuteMsg::Bid {
+            name: "alice".to_string(),
+        };
+        execute(deps, env, info, msg)
+    }
+
+    #[test]
+    fn auction_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_auction(deps.as_mut());
+
+        // the first bid has to cover the price
+        match bid(deps.as_mut(), mock_env(), "alice_key", 9) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::BidTooLow { min_bid }) => assert_eq!(min_bid.u128(), 10),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let res = bid(deps.as_mut(), mock_env(), "alice_key", 10).unwrap();
+        assert!(res.messages.is_empty());
+
+        // later bids have to beat the current bid by the increment
+        match bid(deps.as_mut(), mock_env_at(50), "bob_key", 14) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::BidTooLow { min_bid }) => assert_eq!(min_bid.u128(), 15),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let res = bid(deps.as_mut(), mock_env_at(50), "bob_key", 15).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice_key".to_string(),
+                amount: coins(10, "token"),
+            })
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Auction {
+                name: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: AuctionResponse = from_binary(&res).unwrap();
+        let auction = value.auction.unwrap();
+        assert_eq!("bob_key", auction.bidder.as_str());
+        assert_eq!(coin(15, "token"), auction.bid);
+        assert_eq!(mock_env_at(100).block.time, auction.ends);
+
+        // the window is fixed by the first bid
+        let msg = ExecuteMsg::ClaimAuction {
+            name: "alice".to_string(),
+        };
+        let info = mock_info("bob_key", &[]);
+        match execute(deps.as_mut(), mock_env_at(99), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::AuctionNotEnded { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        match bid(deps.as_mut(), mock_env_at(100), "alice_key", 20) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::AuctionEnded { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("bob_key", &[]);
+        let _res = execute(deps.as_mut(), mock_env_at(100), info, msg)
+            .expect("contract successfully handles ClaimAuction message");
+        assert_name_owner(deps.as_ref(), "alice", "bob_key");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Auction {
+                name: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: AuctionResponse = from_binary(&res).unwrap();
+        assert_eq!(None, value.auction);
+
+        // the name is no longer up for auction
+        match bid(deps.as_mut(), mock_env_at(100), "alice_key", 20) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NameTaken { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn withdraw_keeps_auction_escrow() {
+        let mut deps = mock_dependencies();
+        mock_init_with_auction(deps.as_mut());
+
+        bid(deps.as_mut(), mock_env(), "alice_key", 10).unwrap();
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(10, "token"));
+
+        // the bid of a running auction can not be withdrawn
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(10, "token"),
+            to: None,
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::FundsInEscrow { available, .. }) => assert!(available.is_zero()),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // the outbid bidder can still be refunded
+        bid(deps.as_mut(), mock_env_at(50), "bob_key", 15).unwrap();
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(15, "token"));
+        let info = mock_info("creator", &[]);
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::FundsInEscrow { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // once claimed the winning bid is collected
+        let claim = ExecuteMsg::ClaimAuction {
+            name: "alice".to_string(),
+        };
+        let info = mock_info("bob_key", &[]);
+        execute(deps.as_mut(), mock_env_at(100), info, claim).unwrap();
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env_at(100), info, msg)
+            .expect("contract successfully handles Withdraw message");
+    }
+
+    #[test]
+    fn claim_auction_normalizes_name() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            purchase_price: None,
+            transfer_price: None,
+            registration_period: None,
+            renewal_price: None,
+            grace_period: 0,
+            admin: None,
+            strict_payment: false,
+            length_prices: vec![],
+            auction: Some(AuctionConfig {
+                duration: 100,
+                min_increment: coin(5, "token"),
+            }),
+            sale_fee_bps: 0,
+            unicode_names: true,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+
+        let info = mock_info("alice_key", &coins(10, "token"));
+        let msg = ExecuteMsg::Bid {
+            name: "münchen".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Bid message");
+
+        // the auction is settled under the same spelling it was bid on
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::ClaimAuction {
+            name: "münchen".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env_at(100), info, msg)
+            .expect("contract successfully handles ClaimAuction message");
+        assert_name_owner(deps.as_ref(), "xn--mnchen-3ya", "alice_key");
+    }
+
+    #[test]
+    fn fails_on_price_in_other_denom_than_auction() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            purchase_price: Some(coin(10, "other")),
+            transfer_price: None,
+            registration_period: None,
+            renewal_price: None,
+            grace_period: 0,
+            admin: None,
+            strict_payment: false,
+            length_prices: vec![],
+            auction: Some(AuctionConfig {
+                duration: 100,
+                min_increment: coin(5, "token"),
+            }),
+            sale_fee_bps: 0,
+            unicode_names: false,
+        };
+        let info = mock_info("creator", &[]);
+        assert!(instantiate(deps.as_mut(), mock_env(), info, msg).is_err());
+
+        mock_init_with_auction(deps.as_mut());
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            purchase_price: Some(PriceUpdate::Set(coin(10, "token"))),
+            transfer_price: None,
+            renewal_price: None,
+            length_prices: Some(vec![LengthPrice {
+                length: 3,
+                price: coin(50, "other"),
+            }]),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Std(_)) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn fails_on_register_in_auction_mode() {
+        let mut deps = mock_dependencies();
+        mock_init_with_auction(deps.as_mut());
+
+        let info = mock_info("alice_key", &coins(10, "token"));
+        let msg = ExecuteMsg::Register {
+            name: "alice".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::AuctionOnly {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    fn mock_init_with_sale_fee(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            purchase_price: None,
+            transfer_price: None,
+            registration_period: None,
+            renewal_price: None,
+            grace_period: 0,
+            admin: None,
+            strict_payment: false,
+            length_prices: vec![],
+            auction: None,
+            sale_fee_bps: 250,
+            unicode_names: false,
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    fn mock_alice_lists_name(deps: DepsMut, price: Coin) {
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::ListForSale {
+            name: "alice".to_string(),
+            price,
+        };
+        let _res = execute(deps, mock_env(), info, msg)
+            .expect("contract successfully handles ListForSale message");
+    }
+
+    fn query_listings(deps: Deps, start_after: Option<&str>) -> ListingsResponse {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::Listings {
+                start_after: start_after.map(String::from),
+                limit: None,
+            },
+        )
+        .unwrap();
+        from_binary(&res).unwrap()
+    }
+
+    #[test]
+    fn buy_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_sale_fee(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_lists_name(deps.as_mut(), coin(1000, "token"));
+
+        let msg = ExecuteMsg::Buy {
+            name: "alice".to_string(),
+        };
+        let info = mock_info("bob_key", &coins(999, "token"));
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("bob_key", &coins(1000, "token"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone())
+            .expect("contract successfully handles Buy message");
+
+        // the seller receives the price minus the sale fee
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice_key".to_string(),
+                amount: coins(975, "token"),
+            })
+        );
+        assert_name_owner(deps.as_ref(), "alice", "bob_key");
+
+        // the listing is gone with the sale
+        assert_eq!(0, query_listings(deps.as_ref(), None).listings.len());
+        let info = mock_info("carl_key", &coins(1000, "token"));
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NotListed { name }) => assert_eq!(name, "alice"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn cancel_listing_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_lists_name(deps.as_mut(), coin(1000, "token"));
+
+        let msg = ExecuteMsg::CancelListing {
+            name: "alice".to_string(),
+        };
+        let info = mock_info("bob_key", &[]);
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("alice_key", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles CancelListing message");
+        assert_eq!(0, query_listings(deps.as_ref(), None).listings.len());
+    }
+
+    #[test]
+    fn fails_on_list_for_sale_from_nonowner() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::ListForSale {
+            name: "alice".to_string(),
+            price: coin(1, "token"),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn transfer_removes_listing() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_lists_name(deps.as_mut(), coin(1000, "token"));
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alice".to_string(),
+            to: "bob_key".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Transfer message");
+
+        let info = mock_info("carl_key", &coins(1000, "token"));
+        let msg = ExecuteMsg::Buy {
+            name: "alice".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NotListed { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        assert_name_owner(deps.as_ref(), "alice", "bob_key");
+    }
+
+    #[test]
+    fn listings_query_paginates() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        for name in &["alice", "bob", "carl"] {
+            let info = mock_info("alice_key", &[]);
+            let msg = ExecuteMsg::Register {
+                name: name.to_string(),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg)
+                .expect("contract successfully handles Register message");
+            let info = mock_info("alice_key", &[]);
+            let msg = ExecuteMsg::ListForSale {
+                name: name.to_string(),
+                price: coin(10, "token"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg)
+                .expect("contract successfully handles ListForSale message");
+        }
+
+        let listings = query_listings(deps.as_ref(), None).listings;
+        let names: Vec<_> = listings
+            .iter()
+            .map(|listing| listing.name.as_str())
+            .collect();
+        assert_eq!(vec!["alice", "bob", "carl"], names);
+        assert_eq!("alice_key", listings[0].seller);
+        assert_eq!(coin(10, "token"), listings[0].price);
+
+        let listings = query_listings(deps.as_ref(), Some("alice")).listings;
+        let names: Vec<_> = listings
+            .iter()
+            .map(|listing| listing.name.as_str())
+            .collect();
+        assert_eq!(vec!["bob", "carl"], names);
+    }
+
+    fn query_names_by_owner(deps: Deps, env: Env, owner: &str) -> Vec<String> {
+        let res = query(
+            deps,
+            env,
+            QueryMsg::NamesByOwner {
+                owner: owner.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: NamesResponse = from_binary(&res).unwrap();
+        value.names
+    }
+
+    fn mock_register_name(deps: DepsMut, name: &str, owner: &str) {
+        let info = mock_info(owner, &[]);
+        let msg = ExecuteMsg::Register {
+            name: name.to_string(),
+        };
+        let _res = execute(deps, mock_env(), info, msg)
+            .expect("contract successfully handles Register message");
+    }
+
+    #[test]
+    fn names_by_owner_follows_transfers() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_register_name(deps.as_mut(), "alice", "alice_key");
+        mock_register_name(deps.as_mut(), "alicia", "alice_key");
+        mock_register_name(deps.as_mut(), "bob", "bob_key");
+        mock_alice_creates_subdomain(deps.as_mut(), "pay", "alice_key", false);
+
+        assert_eq!(
+            vec!["alice", "alicia", "pay.alice"],
+            query_names_by_owner(deps.as_ref(), mock_env(), "alice_key")
+        );
+        assert_eq!(
+            vec!["bob"],
+            query_names_by_owner(deps.as_ref(), mock_env(), "bob_key")
+        );
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alicia".to_string(),
+            to: "bob_key".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Transfer message");
+
+        assert_eq!(
+            vec!["alice", "pay.alice"],
+            query_names_by_owner(deps.as_ref(), mock_env(), "alice_key")
+        );
+        assert_eq!(
+            vec!["alicia", "bob"],
+            query_names_by_owner(deps.as_ref(), mock_env(), "bob_key")
+        );
+
+        // pagination
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NamesByOwner {
+                owner: "bob_key".to_string(),
+                start_after: Some("alicia".to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: NamesResponse = from_binary(&res).unwrap();
+        assert_eq!(vec!["bob"], value.names);
+    }
+
+    #[test]
+    fn names_by_owner_skips_expired_names() {
+        let mut deps = mock_dependencies();
+        mock_init_with_expiration(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        assert_eq!(
+            vec!["alice"],
+            query_names_by_owner(deps.as_ref(), mock_env_at(999), "alice_key")
+        );
+        assert!(query_names_by_owner(deps.as_ref(), mock_env_at(1000), "alice_key").is_empty());
+
+        // registering the name again moves it to the new owner
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Register {
+            name: "alice".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env_at(1100), info, msg)
+            .expect("contract successfully handles Register message");
+        assert!(query_names_by_owner(deps.as_ref(), mock_env_at(1100), "alice_key").is_empty());
+        assert_eq!(
+            vec!["alice"],
+            query_names_by_owner(deps.as_ref(), mock_env_at(1100), "bob_key")
+        );
+    }
+
+    #[test]
+    fn all_names_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_register_name(deps.as_mut(), "carl", "carl_key");
+        mock_register_name(deps.as_mut(), "alice", "alice_key");
+        mock_register_name(deps.as_mut(), "bob", "bob_key");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllNames {
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+        let value: NamesResponse = from_binary(&res).unwrap();
+        assert_eq!(vec!["alice", "bob"], value.names);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllNames {
+                start_after: Some("bob".to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: NamesResponse = from_binary(&res).unwrap();
+        assert_eq!(vec!["carl"], value.names);
+    }
+
+    #[test]
+    fn cw721_transfer_nft_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::TransferNft {
+            recipient: "bob_key".to_string(),
+            token_id: "alice".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles TransferNft message");
+
+        // both interfaces see the new owner
+        assert_name_owner(deps.as_ref(), "alice", "bob_key");
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OwnerOf {
+                token_id: "alice".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+        let value: OwnerOfResponse = from_binary(&res).unwrap();
+        assert_eq!("bob_key", value.owner);
+        assert!(value.approvals.is_empty());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Tokens {
+                owner: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: TokensResponse = from_binary(&res).unwrap();
+        assert_eq!(vec!["alice"], value.tokens);
+    }
+
+    #[test]
+    fn cw721_transfer_nft_is_free() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
+        mock_alice_registers_name(deps.as_mut(), &coins(2, "token"));
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Approve {
+            spender: "market".to_string(),
+            token_id: "alice".to_string(),
+            expires: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Approve message");
+
+        // approved spenders transfer without paying the transfer price
+        let info = mock_info("market", &[]);
+        let msg = ExecuteMsg::TransferNft {
+            recipient: "bob_key".to_string(),
+            token_id: "alice".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles TransferNft message");
+        assert!(res.messages.is_empty());
+        assert_name_owner(deps.as_ref(), "alice", "bob_key");
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::TransferNft {
+            recipient: "carol_key".to_string(),
+            token_id: "alice".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles TransferNft message");
+        assert_name_owner(deps.as_ref(), "alice", "carol_key");
+    }
+
+    #[test]
+    fn cw721_approve_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Approve {
+            spender: "market".to_string(),
+            token_id: "alice".to_string(),
+            expires: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Approve message");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OwnerOf {
+                token_id: "alice".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+        let value: OwnerOfResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![Cw721Approval {
+                spender: "market".to_string(),
+                expires: Expiration::Never {},
+            }],
+            value.approvals
+        );
+
+        // the approved spender can transfer, which clears the approvals
+        let info = mock_info("market", &[]);
+        let msg = ExecuteMsg::TransferNft {
+            recipient: "bob_key".to_string(),
+            token_id: "alice".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg.clone())
+            .expect("contract successfully handles TransferNft message");
+        assert_name_owner(deps.as_ref(), "alice", "bob_key");
+
+        let info = mock_info("market", &[]);
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn cw721_revoke_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Approve {
+            spender: "market".to_string(),
+            token_id: "alice".to_string(),
+            expires: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Approve message");
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Revoke {
+            spender: "market".to_string(),
+            token_id: "alice".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Revoke message");
+
+        let info = mock_info("market", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alice".to_string(),
+            to: "market".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn fails_on_expired_approval() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let expires = Expiration::AtHeight(mock_env().block.height + 10);
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Approve {
+            spender: "market".to_string(),
+            token_id: "alice".to_string(),
+            expires: Some(expires),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Approve message");
+
+        let mut env = mock_env();
+        env.block.height += 10;
+        let info = mock_info("market", &[]);
+        let msg = ExecuteMsg::TransferNft {
+            recipient: "market".to_string(),
+            token_id: "alice".to_string(),
+        };
+        match execute(deps.as_mut(), env.clone(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // expired approvals are only listed on request
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::OwnerOf {
+                token_id: "alice".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+        let value: OwnerOfResponse = from_binary(&res).unwrap();
+        assert!(value.approvals.is_empty());
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::OwnerOf {
+                token_id: "alice".to_string(),
+                include_expired: Some(true),
+            },
+        )
+        .unwrap();
+        let value: OwnerOfResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.approvals.len());
+    }
+
+    #[test]
+    fn cw721_nft_info_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_expiration(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::SetRecord {
+            name: "alice".to_string(),
+            record: Record::Url("https://alice.example".to_string()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles SetRecord message");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo {
+                token_id: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: NftInfoResponse<NameMetadata> = from_binary(&res).unwrap();
+        assert_eq!(Some("https://alice.example".to_string()), value.token_uri);
+        assert_eq!(Some(mock_env_at(1000).block.time), value.extension.expires);
+
+        // expired names are unknown tokens
+        let res = query(
+            deps.as_ref(),
+            mock_env_at(1000),
+            QueryMsg::NftInfo {
+                token_id: "alice".to_string(),
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    fn mock_reserve(deps: DepsMut, pattern: ReservedPattern, claimant: Option<&str>) {
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reserve {
+            pattern,
+            claimant: claimant.map(String::from),
+        };
+        let _res = execute(deps, mock_env(), info, msg)
+            .expect("contract successfully handles Reserve message");
+    }
+
+    fn query_reserved(deps: Deps, name: &str) -> ReservedResponse {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::Reserved {
+                name: name.to_string(),
+            },
+        )
+        .unwrap();
+        from_binary(&res).unwrap()
+    }
+
+    #[test]
+    fn reserve_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_reserve(
+            deps.as_mut(),
+            ReservedPattern::Name("alice".to_string()),
+            Some("alice_key"),
+        );
+        mock_reserve(
+            deps.as_mut(),
+            ReservedPattern::Prefix("bank".to_string()),
+            None,
+        );
+
+        assert_eq!(
+            ReservedResponse {
+                reserved: true,
+                claimant: Some("alice_key".to_string()),
+            },
+            query_reserved(deps.as_ref(), "alice")
+        );
+        assert_eq!(
+            ReservedResponse {
+                reserved: true,
+                claimant: None,
+            },
+            query_reserved(deps.as_ref(), "bankofalice")
+        );
+        assert_eq!(
+            ReservedResponse {
+                reserved: false,
+                claimant: None,
+            },
+            query_reserved(deps.as_ref(), "bob")
+        );
+
+        // the claimant and the admin can register reserved names
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        assert_name_owner(deps.as_ref(), "alice", "alice_key");
+        mock_register_name(deps.as_mut(), "bankofbob", "creator");
+        assert_name_owner(deps.as_ref(), "bankofbob", "creator");
+    }
+
+    #[test]
+    fn fails_on_register_reserved_name() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_reserve(
+            deps.as_mut(),
+            ReservedPattern::Name("alice".to_string()),
+            Some("alice_key"),
+        );
+        mock_reserve(
+            deps.as_mut(),
+            ReservedPattern::Prefix("bank".to_string()),
+            None,
+        );
+
+        for name in ["alice", "bankofbob"] {
+            let info = mock_info("bob_key", &[]);
+            let msg = ExecuteMsg::Register {
+                name: name.to_string(),
+            };
+            match execute(deps.as_mut(), mock_env(), info, msg) {
+                Ok(_) => panic!("Must return error"),
+                Err(ContractError::NameReserved { .. }) => {}
+                Err(e) => panic!("Unexpected error: {:?}", e),
+            }
+        }
+
+        // once unreserved anyone can register the name
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Unreserve {
+            pattern: ReservedPattern::Prefix("bank".to_string()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Unreserve message");
+        assert!(!query_reserved(deps.as_ref(), "bankofbob").reserved);
+        mock_register_name(deps.as_mut(), "bankofbob", "bob_key");
+        assert_name_owner(deps.as_ref(), "bankofbob", "bob_key");
+    }
+
+    #[test]
+    fn fails_on_bid_for_reserved_name() {
+        let mut deps = mock_dependencies();
+        mock_init_with_auction(deps.as_mut());
+        mock_reserve(
+            deps.as_mut(),
+            ReservedPattern::Name("alice".to_string()),
+            Some("alice_key"),
+        );
+
+        match bid(deps.as_mut(), mock_env(), "bob_key", 10) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NameReserved { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let _res = bid(deps.as_mut(), mock_env(), "alice_key", 10)
+            .expect("claimant can bid for a reserved name");
+    }
+
+    #[test]
+    fn fails_on_reserve_by_non_admin() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Reserve {
+            pattern: ReservedPattern::Name("alice".to_string()),
+            claimant: None,
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    fn mock_init_with_unicode_names(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            purchase_price: None,
+            transfer_price: None,
+            registration_period: None,
+            renewal_price: None,
+            grace_period: 0,
+            admin: None,
+            strict_payment: false,
+            length_prices: vec![LengthPrice {
+                length: 7,
+                price: coin(10, "token"),
+            }],
+            auction: None,
+            sale_fee_bps: 0,
+            unicode_names: true,
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    fn register_name(deps: DepsMut, name: &str, funds: &[Coin]) -> Result<Response, ContractError> {
+        let info = mock_info("alice_key", funds);
+        let msg = ExecuteMsg::Register {
+            name: name.to_string(),
+        };
+        execute(deps, mock_env(), info, msg)
+    }
+
+    #[test]
+    fn register_unicode_name_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_unicode_names(deps.as_mut());
+
+        // names are priced by their length in characters
+        assert_price(deps.as_ref(), "münchen", Some(coin(10, "token")));
+        assert_price(deps.as_ref(), "xn--mnchen-3ya", Some(coin(10, "token")));
+
+        // decomposed and upper case input is normalized
+        let _res = register_name(deps.as_mut(), "Mu\u{308}nchen", &coins(10, "token"))
+            .expect("contract successfully handles Register message");
+        assert_name_owner(deps.as_ref(), "xn--mnchen-3ya", "alice_key");
+
+        // the unicode and punycode forms refer to the same name
+        for name in ["münchen", "xn--mnchen-3ya"] {
+            match register_name(deps.as_mut(), name, &coins(10, "token")) {
+                Ok(_) => panic!("Must return error"),
+                Err(ContractError::NameTaken { name }) => assert_eq!("xn--mnchen-3ya", name),
+                Err(e) => panic!("Unexpected error: {:?}", e),
+            }
+        }
+
+        // ascii names are lowercased and stored as they are
+        let _res = register_name(deps.as_mut(), "LOUD", &[])
+            .expect("contract successfully handles Register message");
+        assert_name_owner(deps.as_ref(), "loud", "alice_key");
+    }
+
+    #[test]
+    fn fails_on_invalid_unicode_name() {
+        let mut deps = mock_dependencies();
+        mock_init_with_unicode_names(deps.as_mut());
+
+        match register_name(deps.as_mut(), "smile\u{1F600}", &[]) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InvalidCharacter { c, .. }) => assert_eq!(c, '\u{1F600}'),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // latin with a cyrillic 'а'
+        match register_name(deps.as_mut(), "p\u{430}ypal", &[]) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InvalidCharacter { c, reason }) => {
+                assert_eq!(c, '\u{430}');
+                assert_eq!(reason, InvalidCharacterReason::MixedScript);
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // all cyrillic, but looks like "coco"
+        match register_name(deps.as_mut(), "\u{441}\u{43e}\u{441}\u{43e}", &[]) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InvalidCharacter {
+                reason: InvalidCharacterReason::Confusable { skeleton },
+                ..
+            }) => assert_eq!("coco", skeleton),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // punycode of an ascii name is not canonical
+        match register_name(deps.as_mut(), "xn--alice-", &[]) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InvalidPunycode { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // length is counted in characters
+        match register_name(deps.as_mut(), "ün", &[]) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NameTooShort { length, .. }) => assert_eq!(2, length),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn reserve_normalizes_unicode_patterns() {
+        let mut deps = mock_dependencies();
+        mock_init_with_unicode_names(deps.as_mut());
+        mock_reserve(
+            deps.as_mut(),
+            ReservedPattern::Name("Café".to_string()),
+            None,
+        );
+        mock_reserve(
+            deps.as_mut(),
+            ReservedPattern::Prefix("MÜN".to_string()),
+            None,
+        );
+
+        assert!(query_reserved(deps.as_ref(), "xn--caf-dma").reserved);
+        assert!(query_reserved(deps.as_ref(), "xn--mnchen-3ya").reserved);
+        for name in ["café", "münchen"] {
+            match register_name(deps.as_mut(), name, &coins(10, "token")) {
+                Ok(_) => panic!("Must return error"),
+                Err(ContractError::NameReserved { .. }) => {}
+                Err(e) => panic!("Unexpected error: {:?}", e),
+            }
+        }
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Unreserve {
+            pattern: ReservedPattern::Name("café".to_string()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Unreserve message");
+        assert!(!query_reserved(deps.as_ref(), "xn--caf-dma").reserved);
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reserve {
+            pattern: ReservedPattern::Prefix("smile\u{1F600}".to_string()),
+            claimant: None,
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InvalidCharacter { c, .. }) => assert_eq!(c, '\u{1F600}'),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn unicode_names_are_looked_up_normalized() {
+        let mut deps = mock_dependencies();
+        mock_init_with_unicode_names(deps.as_mut());
+        let _res = register_name(deps.as_mut(), "bücher", &[])
+            .expect("contract successfully handles Register message");
+
+        // every spelling of a name refers to the stored punycode form
+        for name in ["Bücher", "bu\u{308}cher", "xn--bcher-kva"] {
+            assert_name_owner(deps.as_ref(), name, "alice_key");
+        }
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "BÜCHER".to_string(),
+            to: "bob_key".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Transfer message");
+        assert_name_owner(deps.as_ref(), "Bücher", "bob_key");
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::CreateSubdomain {
+            parent: "Bücher".to_string(),
+            label: "Blog".to_string(),
+            owner: "carol_key".to_string(),
+            reclaimable: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles CreateSubdomain message");
+        assert_name_owner(deps.as_ref(), "blog.xn--bcher-kva", "carol_key");
+        assert_name_owner(deps.as_ref(), "blog.bücher", "carol_key");
+
+        mock_reserve(
+            deps.as_mut(),
+            ReservedPattern::Name("xn--caf-dma".to_string()),
+            None,
+        );
+        assert!(query_reserved(deps.as_ref(), "Café").reserved);
+        assert!(!query_reserved(deps.as_ref(), "Bücher").reserved);
+    }
+
+    fn mock_alice_offers_name(deps: DepsMut, to: &str) {
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::OfferTransfer {
+            name: "alice".to_string(),
+            to: to.to_string(),
+        };
+        let _res = execute(deps, mock_env(), info, msg)
+            .expect("contract successfully handles OfferTransfer message");
+    }
+
+    fn query_transfer_offer(deps: Deps, name: &str) -> Option<TransferOfferInfo> {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::TransferOffer {
+                name: name.to_string(),
+            },
+        )
+        .unwrap();
+        let value: TransferOfferResponse = from_binary(&res).unwrap();
+        value.offer
+    }
+
+    #[test]
+    fn accept_transfer_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
+        mock_alice_registers_name(deps.as_mut(), &coins(2, "token"));
+        mock_alice_offers_name(deps.as_mut(), "bob_key");
+
+        // the name stays with alice until bob accepts
+        assert_name_owner(deps.as_ref(), "alice", "alice_key");
+        let offer = TransferOfferInfo {
+            name: "alice".to_string(),
+            from: "alice_key".to_string(),
+            to: "bob_key".to_string(),
+        };
+        assert_eq!(
+            Some(offer.clone()),
+            query_transfer_offer(deps.as_ref(), "alice")
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TransferOffers {
+                recipient: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: TransferOffersResponse = from_binary(&res).unwrap();
+        assert_eq!(vec![offer], value.offers);
+
+        let info = mock_info("bob_key", &coins(2, "token"));
+        let msg = ExecuteMsg::AcceptTransfer {
+            name: "alice".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles AcceptTransfer message");
+
+        assert_name_owner(deps.as_ref(), "alice", "bob_key");
+        assert_eq!(None, query_transfer_offer(deps.as_ref(), "alice"));
+    }
+
+    #[test]
+    fn fails_on_accept_transfer_by_others() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
+        mock_alice_registers_name(deps.as_mut(), &coins(2, "token"));
+        mock_alice_offers_name(deps.as_mut(), "bob_key");
+
+        let info = mock_info("charlie_key", &coins(2, "token"));
+        let msg = ExecuteMsg::AcceptTransfer {
+            name: "alice".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // the transfer price is charged on acceptance
+        let info = mock_info("bob_key", &coins(1, "token"));
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        assert_name_owner(deps.as_ref(), "alice", "alice_key");
+    }
+
+    #[test]
+    fn cancel_transfer_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_offers_name(deps.as_mut(), "bob_key");
+
+        let msg = ExecuteMsg::CancelTransfer {
+            name: "alice".to_string(),
+        };
+        let info = mock_info("bob_key", &[]);
+        match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let info = mock_info("alice_key", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles CancelTransfer message");
+        assert_eq!(None, query_transfer_offer(deps.as_ref(), "alice"));
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::AcceptTransfer {
+            name: "alice".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NoTransferOffer { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn transfer_clears_offer() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_offers_name(deps.as_mut(), "bob_key");
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alice".to_string(),
+            to: "charlie_key".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles Transfer message");
+
+        assert_eq!(None, query_transfer_offer(deps.as_ref(), "alice"));
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::AcceptTransfer {
+            name: "alice".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NoTransferOffer { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    fn mock_alice_approves_operator(
+        deps: DepsMut,
+        name: Option<&str>,
+        can_transfer: bool,
+        expires: Option<Expiration>,
+    ) {
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::ApproveOperator {
+            operator: "operator_key".to_string(),
+            name: name.map(String::from),
+            can_transfer,
+            expires,
+        };
+        let _res = execute(deps, mock_env(), info, msg)
+            .expect("contract successfully handles ApproveOperator message");
+    }
+
+    fn operator_sets_url(deps: DepsMut, env: Env, name: &str) -> Result<Response, ContractError> {
+        let info = mock_info("operator_key", &[]);
+        let msg = ExecuteMsg::SetRecord {
+            name: name.to_string(),
+            record: Record::Url("https://operator.example".to_string()),
+        };
+        execute(deps, env, info, msg)
+    }
+
+    #[test]
+    fn operator_can_manage_name() {
+        let mut deps = mock_dependencies();
+        mock_init_with_expiration(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_approves_operator(deps.as_mut(), Some("alice"), false, None);
+
+        let _res = operator_sets_url(deps.as_mut(), mock_env(), "alice")
+            .expect("operator can set records");
+        assert_eq!(
+            Some("https://operator.example".to_string()),
+            query_records(deps.as_ref(), "alice").url
+        );
+
+        let info = mock_info("operator_key", &coins(3, "token"));
+        let msg = ExecuteMsg::Renew {
+            name: "alice".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).expect("operator can renew");
+
+        // but not transfer
+        let info = mock_info("operator_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alice".to_string(),
+            to: "operator_key".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        assert_name_owner(deps.as_ref(), "alice", "alice_key");
+    }
+
+    #[test]
+    fn operator_for_all_names_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_register_name(deps.as_mut(), "alicia", "alice_key");
+        mock_register_name(deps.as_mut(), "bob", "bob_key");
+        mock_alice_approves_operator(deps.as_mut(), None, true, None);
+
+        let _res = operator_sets_url(deps.as_mut(), mock_env(), "alice")
+            .expect("operator can set records");
+        match operator_sets_url(deps.as_mut(), mock_env(), "bob") {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("operator_key", &[]);
+        let msg = ExecuteMsg::Transfer {
+            name: "alicia".to_string(),
+            to: "bob_key".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("operator allowed to transfer can transfer");
+        assert_name_owner(deps.as_ref(), "alicia", "bob_key");
+
+        // the operator was approved by alice and has no say over bob's names
+        match operator_sets_url(deps.as_mut(), mock_env(), "alicia") {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn revoke_operator_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        mock_alice_approves_operator(deps.as_mut(), Some("alice"), false, None);
+        mock_alice_approves_operator(deps.as_mut(), None, false, None);
+
+        for name in [Some("alice".to_string()), None] {
+            let info = mock_info("alice_key", &[]);
+            let msg = ExecuteMsg::RevokeOperator {
+                operator: "operator_key".to_string(),
+                name,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg)
+                .expect("contract successfully handles RevokeOperator message");
+        }
+
+        match operator_sets_url(deps.as_mut(), mock_env(), "alice") {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn operator_approval_expires() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_registers_name(deps.as_mut(), &[]);
+        let expires = Expiration::AtHeight(mock_env().block.height + 10);
+        mock_alice_approves_operator(deps.as_mut(), Some("alice"), false, Some(expires));
+        mock_alice_approves_operator(deps.as_mut(), None, true, None);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Operators {
+                name: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: OperatorsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![
+                OperatorInfo {
+                    operator: "operator_key".to_string(),
+                    all_names: false,
+                    can_transfer: false,
+                    expires,
+                },
+                OperatorInfo {
+                    operator: "operator_key".to_string(),
+                    all_names: true,
+                    can_transfer: true,
+                    expires: Expiration::Never {},
+                },
+            ],
+            value.operators
+        );
+
+        // expired approvals are ignored
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::RevokeOperator {
+            operator: "operator_key".to_string(),
+            name: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract successfully handles RevokeOperator message");
+        let mut env = mock_env();
+        env.block.height += 10;
+        match operator_sets_url(deps.as_mut(), env.clone(), "alice") {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::Operators {
+                name: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: OperatorsResponse = from_binary(&res).unwrap();
+        assert!(value.operators.is_empty());
+    }
 }
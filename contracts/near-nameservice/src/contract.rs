@@ -0,0 +1,268 @@
+use crate::error::ContractError;
+use crate::*;
+
+const MIN_NAME_LENGTH: u64 = 3;
+const MAX_NAME_LENGTH: u64 = 64;
+
+#[near_bindgen]
+impl Contract {
+    #[init]
+    pub fn instantiate(purchase_price: Option<U128>, transfer_price: Option<U128>) -> Self {
+        Self {
+            purchase_price: purchase_price.map(|price| price.0),
+            transfer_price: transfer_price.map(|price| price.0),
+            records: UnorderedMap::new(RECORDS_KEY),
+        }
+    }
+
+    /// Registers an available name to the caller. The attached deposit has to cover
+    /// the purchase price and the storage staking of the record, the rest is refunded.
+    #[payable]
+    pub fn register(&mut self, name: String) {
+        // we only need to check here - at point of registration
+        if let Err(err) = validate_name(&name) {
+            env::panic_str(&err.to_string());
+        }
+        assert!(
+            self.records.get(&name).is_none(),
+            "{}",
+            ContractError::NameTaken { name }
+        );
+
+        let initial_usage = env::storage_usage();
+        let record = NameRecord {
+            owner: env::predecessor_account_id(),
+        };
+        self.records.insert(&name, &record);
+
+        collect_deposit(self.purchase_price, initial_usage);
+    }
+
+    /// Moves a name to a new owner. The attached deposit has to cover the transfer
+    /// price and any additional storage taken by the record, the rest is refunded.
+    #[payable]
+    pub fn transfer(&mut self, name: String, to: AccountId) {
+        let mut record = match self.records.get(&name) {
+            Some(record) => record,
+            None => env::panic_str(&ContractError::NameNotExists { name }.to_string()),
+        };
+        assert!(
+            env::predecessor_account_id() == record.owner,
+            "{}",
+            ContractError::Unauthorized
+        );
+
+        let initial_usage = env::storage_usage();
+        record.owner = to;
+        self.records.insert(&name, &record);
+
+        collect_deposit(self.transfer_price, initial_usage);
+    }
+
+    pub fn query_resolver(&self, name: String) -> Option<AccountId> {
+        self.records.get(&name).map(|record| record.owner)
+    }
+
+    pub fn query_config(&self) -> Config {
+        Config {
+            purchase_price: self.purchase_price.map(U128),
+            transfer_price: self.transfer_price.map(U128),
+        }
+    }
+}
+
+/// Checks that the attached deposit covers the price and the staking of the storage
+/// used since `initial_usage`, the excess is refunded to the caller
+fn collect_deposit(price: Option<Balance>, initial_usage: StorageUsage) {
+    let required = price.unwrap_or_default() + storage_cost(initial_usage);
+    let deposit = env::attached_deposit();
+    assert!(
+        deposit >= required,
+        "{}",
+        ContractError::InsufficientFundsSend
+    );
+
+    if deposit > required {
+        send_tokens(env::predecessor_account_id(), deposit - required);
+    }
+}
+
+// this is a helper to move the tokens, so the business logic is easy to read
+fn send_tokens(to_address: AccountId, amount: Balance) -> Promise {
+    Promise::new(to_address).transfer(amount)
+}
+
+fn invalid_char(c: char) -> bool {
+    let is_valid = c.is_ascii_digit() || c.is_ascii_lowercase() || (c == '-' || c == '_');
+    !is_valid
+}
+
+/// validate_name returns an error if the name is invalid
+/// (we require 3-64 lowercase ascii letters, numbers, or - _)
+fn validate_name(name: &str) -> Result<(), ContractError> {
+    let length = name.len() as u64;
+    if length < MIN_NAME_LENGTH {
+        Err(ContractError::NameTooShort {
+            length,
+            min_length: MIN_NAME_LENGTH,
+        })
+    } else if length > MAX_NAME_LENGTH {
+        Err(ContractError::NameTooLong {
+            length,
+            max_length: MAX_NAME_LENGTH,
+        })
+    } else {
+        match name.find(invalid_char) {
+            None => Ok(()),
+            Some(bytepos_invalid_char_start) => {
+                let c = name[bytepos_invalid_char_start..].chars().next().unwrap();
+                Err(ContractError::InvalidCharacter { c })
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, ONE_NEAR};
+
+    use super::*;
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .current_account_id(accounts(0))
+            .signer_account_id(predecessor_account_id.clone())
+            .predecessor_account_id(predecessor_account_id);
+        builder
+    }
+
+    fn init_with_price() -> Contract {
+        Contract::instantiate(Some(U128(ONE_NEAR)), Some(U128(2 * ONE_NEAR)))
+    }
+
+    #[test]
+    fn proper_initialization() {
+        let state = init_with_price();
+        assert_eq!(
+            state.query_config(),
+            Config {
+                purchase_price: Some(U128(ONE_NEAR)),
+                transfer_price: Some(U128(2 * ONE_NEAR)),
+            }
+        );
+    }
+
+    #[test]
+    fn register_available_name_and_query_works() {
+        let mut context = get_context(accounts(1));
+        testing_env!(context.attached_deposit(2 * ONE_NEAR).build());
+
+        let mut state = init_with_price();
+        state.register("alice".to_string());
+
+        assert_eq!(state.query_resolver("alice".to_string()), Some(accounts(1)));
+        assert_eq!(state.query_resolver("bob".to_string()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient funds sent")]
+    fn fails_on_register_without_storage_deposit() {
+        let mut context = get_context(accounts(1));
+        // covers the price, but not the storage of the record
+        testing_env!(context.attached_deposit(ONE_NEAR).build());
+
+        let mut state = init_with_price();
+        state.register("alice".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Name has been taken (name alice)")]
+    fn fails_on_register_already_taken_name() {
+        let mut context = get_context(accounts(1));
+        testing_env!(context.attached_deposit(2 * ONE_NEAR).build());
+
+        let mut state = init_with_price();
+        state.register("alice".to_string());
+
+        testing_env!(context
+            .predecessor_account_id(accounts(2))
+            .attached_deposit(2 * ONE_NEAR)
+            .build());
+        state.register("alice".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Name too short (length 2 min_length 3)")]
+    fn fails_on_register_too_short_name() {
+        let mut context = get_context(accounts(1));
+        testing_env!(context.attached_deposit(2 * ONE_NEAR).build());
+
+        let mut state = init_with_price();
+        state.register("hi".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid character(char L")]
+    fn fails_on_register_invalid_character() {
+        let mut context = get_context(accounts(1));
+        testing_env!(context.attached_deposit(2 * ONE_NEAR).build());
+
+        let mut state = init_with_price();
+        state.register("LOUD".to_string());
+    }
+
+    #[test]
+    fn transfer_works() {
+        let mut context = get_context(accounts(1));
+        testing_env!(context.attached_deposit(2 * ONE_NEAR).build());
+
+        let mut state = init_with_price();
+        state.register("alice".to_string());
+
+        testing_env!(context.attached_deposit(3 * ONE_NEAR).build());
+        state.transfer("alice".to_string(), accounts(2));
+
+        assert_eq!(state.query_resolver("alice".to_string()), Some(accounts(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient funds sent")]
+    fn fails_on_transfer_insufficient_deposit() {
+        let mut context = get_context(accounts(1));
+        testing_env!(context.attached_deposit(2 * ONE_NEAR).build());
+
+        let mut state = init_with_price();
+        state.register("alice".to_string());
+
+        testing_env!(context.attached_deposit(ONE_NEAR).build());
+        state.transfer("alice".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn fails_on_transfer_from_nonowner() {
+        let mut context = get_context(accounts(1));
+        testing_env!(context.attached_deposit(2 * ONE_NEAR).build());
+
+        let mut state = init_with_price();
+        state.register("alice".to_string());
+
+        testing_env!(context
+            .predecessor_account_id(accounts(2))
+            .attached_deposit(3 * ONE_NEAR)
+            .build());
+        state.transfer("alice".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Name does not exist (name alice)")]
+    fn fails_on_transfer_non_existent() {
+        let mut context = get_context(accounts(1));
+        testing_env!(context.attached_deposit(3 * ONE_NEAR).build());
+
+        let mut state = init_with_price();
+        state.transfer("alice".to_string(), accounts(2));
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug, PartialEq)]
+pub enum ContractError {
+    Unauthorized,
+    InsufficientFundsSend,
+    NameNotExists { name: String },
+    NameTaken { name: String },
+    NameTooShort { length: u64, min_length: u64 },
+    NameTooLong { length: u64, max_length: u64 },
+    InvalidCharacter { c: char },
+}
+
+impl Display for ContractError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            ContractError::Unauthorized => write!(f, "Unauthorized"),
+            ContractError::InsufficientFundsSend => write!(f, "Insufficient funds sent"),
+            ContractError::NameNotExists { name } => {
+                write!(f, "Name does not exist (name {})", name)
+            }
+            ContractError::NameTaken { name } => write!(f, "Name has been taken (name {})", name),
+            ContractError::NameTooShort { length, min_length } => write!(
+                f,
+                "Name too short (length {} min_length {})",
+                length, min_length
+            ),
+            ContractError::NameTooLong { length, max_length } => write!(
+                f,
+                "Name too long (length {} min_length {})",
+                length, max_length
+            ),
+            ContractError::InvalidCharacter { c } => write!(f, "Invalid character(char {}", c),
+        }
+    }
+}
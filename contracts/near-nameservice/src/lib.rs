@@ -0,0 +1,18 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U128;
+use near_sdk::{env, near_bindgen, AccountId, Balance, PanicOnDefault, Promise, StorageUsage};
+
+pub mod contract;
+mod error;
+pub mod state;
+
+use crate::state::*;
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Contract {
+    purchase_price: Option<Balance>,
+    transfer_price: Option<Balance>,
+    records: UnorderedMap<String, NameRecord>,
+}
@@ -0,0 +1,23 @@
+use near_sdk::serde::Serialize;
+
+use crate::*;
+
+pub static RECORDS_KEY: &[u8] = b"records";
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct NameRecord {
+    pub owner: AccountId,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Config {
+    pub purchase_price: Option<U128>,
+    pub transfer_price: Option<U128>,
+}
+
+/// Staking required for the storage used since `initial_usage`
+pub fn storage_cost(initial_usage: StorageUsage) -> Balance {
+    let used = env::storage_usage().saturating_sub(initial_usage);
+    Balance::from(used) * env::storage_byte_cost()
+}
@@ -2,7 +2,9 @@ use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use std::env::current_dir;
 use std::fs::create_dir_all;
 
-use cw_voting::msg::{ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, TokenStakeResponse};
+use cw_voting::msg::{
+    ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, TokenStakeResponse, VotersResponse,
+};
 use cw_voting::state::State;
 
 fn main() {
@@ -17,4 +19,5 @@ fn main() {
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(State), &out_dir);
     export_schema(&schema_for!(TokenStakeResponse), &out_dir);
+    export_schema(&schema_for!(VotersResponse), &out_dir);
 }
@@ -2,13 +2,15 @@ use crate::coin_helpers::validate_sent_sufficient_coin;
 use crate::error::ContractError;
 use crate::msg::{
     CreatePollResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, TokenStakeResponse,
+    VoterInfo, VotersResponse,
 };
 use crate::state::{
-    bank, bank_read, config, config_read, poll, poll_read, Poll, PollStatus, State, Voter,
+    bank, bank_read, config, config_read, poll, poll_read, poll_voters, poll_voters_read, Choice,
+    Poll, PollMode, PollOption, PollStatus, State, TokenManager, VoteOption, Voter,
 };
 use cosmwasm_std::{
-    attr, coin, entry_point, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env,
-    MessageInfo, Response, StdError, StdResult, Storage, Uint128,
+    attr, coin, entry_point, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128,
 };
 
 pub const VOTING_TOKEN: &str = "voting_token";
@@ -16,6 +18,14 @@ pub const DEFAULT_END_HEIGHT_BLOCKS: &u64 = &100_800_u64;
 const MIN_STAKE_AMOUNT: u128 = 1;
 const MIN_DESC_LENGTH: u64 = 3;
 const MAX_DESC_LENGTH: u64 = 64;
+const DEFAULT_THRESHOLD_PERCENT: u64 = 50;
+const DEFAULT_VETO_THRESHOLD_PERMILLE: u128 = 334;
+const MIN_POLL_OPTIONS: usize = 2;
+const MAX_POLL_OPTIONS: usize = 16;
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
 
 #[entry_point]
 pub fn instantiate(
@@ -24,11 +34,25 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    let quorum = msg.quorum.unwrap_or_else(Decimal::zero);
+    let threshold = msg
+        .threshold
+        .unwrap_or_else(|| Decimal::percent(DEFAULT_THRESHOLD_PERCENT));
+    let veto_threshold = msg
+        .veto_threshold
+        .unwrap_or_else(|| Decimal::from_ratio(DEFAULT_VETO_THRESHOLD_PERMILLE, 1000u128));
+    validate_quorum(quorum)?;
+    validate_threshold(threshold)?;
+    validate_veto_threshold(veto_threshold)?;
+
     let state = State {
         denom: msg.denom,
         owner: info.sender,
         poll_count: 0,
         staked_tokens: Uint128::zero(),
+        quorum,
+        threshold,
+        veto_threshold,
     };
 
     config(deps.storage).save(&state)?;
@@ -53,20 +77,31 @@ pub fn execute(
             vote,
             weight,
         } => cast_vote(deps, env, info, poll_id, vote, weight),
+        ExecuteMsg::CastChoiceVote { poll_id, choices } => {
+            cast_choice_vote(deps, env, info, poll_id, choices)
+        }
         ExecuteMsg::EndPoll { poll_id } => end_poll(deps, env, info, poll_id),
         ExecuteMsg::CreatePoll {
-            quorum_percentage,
+            quorum,
+            threshold,
+            veto_threshold,
             description,
             start_height,
             end_height,
+            options,
+            mode,
         } => create_poll(
             deps,
             env,
             info,
-            quorum_percentage,
+            quorum,
+            threshold,
+            veto_threshold,
             description,
             start_height,
             end_height,
+            options,
+            mode,
         ),
     }
 }
@@ -110,7 +145,8 @@ pub fn withdraw_voting_tokens(
     let sender_address_raw = info.sender.as_str().as_bytes();
 
     if let Some(mut token_manager) = bank_read(deps.storage).may_load(sender_address_raw)? {
-        let largest_staked = locked_amount(&sender_address_raw, deps.storage);
+        unlock_tokens(deps.storage, &mut token_manager)?;
+        let largest_staked = locked_amount(&token_manager);
         let withdraw_amount = amount.unwrap_or(token_manager.token_balance);
         if largest_staked + withdraw_amount > token_manager.token_balance {
             let max_amount = token_manager.token_balance.checked_sub(largest_staked)?;
@@ -152,19 +188,52 @@ fn validate_description(description: &str) -> Result<(), ContractError> {
     }
 }
 
-/// validate_quorum_percentage returns an error if the quorum_percentage is invalid
-/// (we require 0-100)
-fn validate_quorum_percentage(quorum_percentage: Option<u8>) -> Result<(), ContractError> {
-    match quorum_percentage {
-        Some(qp) => {
-            if qp > 100 {
-                return Err(ContractError::PollQuorumPercentageMismatch {
-                    quorum_percentage: qp,
-                });
-            }
-            Ok(())
-        }
-        None => Ok(()),
+/// validate_quorum returns an error if the quorum is invalid
+/// (we require 0-100%)
+fn validate_quorum(quorum: Decimal) -> Result<(), ContractError> {
+    if quorum > Decimal::one() {
+        Err(ContractError::PollQuorumOutOfRange { quorum })
+    } else {
+        Ok(())
+    }
+}
+
+/// validate_threshold returns an error if the threshold is invalid
+/// (we require 0-100%)
+fn validate_threshold(threshold: Decimal) -> Result<(), ContractError> {
+    if threshold > Decimal::one() {
+        Err(ContractError::PollThresholdOutOfRange { threshold })
+    } else {
+        Ok(())
+    }
+}
+
+/// validate_veto_threshold returns an error if the veto_threshold is invalid
+/// (we require 0-100%)
+fn validate_veto_threshold(veto_threshold: Decimal) -> Result<(), ContractError> {
+    if veto_threshold > Decimal::one() {
+        Err(ContractError::PollVetoThresholdOutOfRange { veto_threshold })
+    } else {
+        Ok(())
+    }
+}
+
+/// validate_options returns an error if the options of a multiple-choice poll are invalid
+/// (we require 2-16 distinct, non-empty names)
+fn validate_options(options: &[String]) -> Result<(), ContractError> {
+    let invalid = options.len() < MIN_POLL_OPTIONS
+        || options.len() > MAX_POLL_OPTIONS
+        || options
+            .iter()
+            .enumerate()
+            .any(|(i, name)| name.is_empty() || options[..i].contains(name));
+    if invalid {
+        Err(ContractError::PollInvalidOptions {
+            min_options: MIN_POLL_OPTIONS,
+            max_options: MAX_POLL_OPTIONS,
+        })
+    } else {
+        Ok(())
     }
 }
 
@@ -178,20 +247,43 @@ fn validate_end_height(end_height: Option<u64>, env: Env) -> Result<(), Contract
 }
 
 /// create a new poll
+#[allow(clippy::too_many_arguments)]
 pub fn create_poll(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    quorum_percentage: Option<u8>,
+    quorum: Option<Decimal>,
+    threshold: Option<Decimal>,
+    veto_threshold: Option<Decimal>,
     description: String,
     start_height: Option<u64>,
     end_height: Option<u64>,
+    options: Option<Vec<String>>,
+    mode: Option<PollMode>,
 ) -> Result<Response, ContractError> {
-    validate_quorum_percentage(quorum_percentage)?;
+    match &options {
+        Some(options) => validate_options(options)?,
+        None if mode.is_some() => return Err(ContractError::PollModeWithoutOptions {}),
+        None => {}
+    }
+    if let Some(quorum) = quorum {
+        validate_quorum(quorum)?;
+    }
+    if let Some(threshold) = threshold {
+        validate_threshold(threshold)?;
+    }
+    if let Some(veto_threshold) = veto_threshold {
+        validate_veto_threshold(veto_threshold)?;
+    }
     validate_end_height(end_height, env.clone())?;
     validate_description(&description)?;
 
     let mut state = config(deps.storage).load()?;
+    // polls fall back to the defaults of the config
+    let quorum = quorum.unwrap_or(state.quorum);
+    let threshold = threshold.unwrap_or(state.threshold);
+    let veto_threshold = veto_threshold.unwrap_or(state.veto_threshold);
+
     let poll_count = state.poll_count;
     let poll_id = poll_count + 1;
     state.poll_count = poll_id;
@@ -199,14 +291,27 @@ pub fn create_poll(
     let new_poll = Poll {
         creator: info.sender,
         status: PollStatus::InProgress,
-        quorum_percentage,
+        quorum,
+        threshold,
+        veto_threshold,
         yes_votes: Uint128::zero(),
         no_votes: Uint128::zero(),
-        voters: vec![],
-        voter_info: vec![],
+        abstain_votes: Uint128::zero(),
+        veto_votes: Uint128::zero(),
+        options: options
+            .unwrap_or_default()
+            .into_iter()
+            .map(|name| PollOption {
+                name,
+                votes: Uint128::zero(),
+            })
+            .collect(),
+        mode: mode.unwrap_or(PollMode::SingleChoice),
+        winning_option: None,
         end_height: end_height.unwrap_or(env.block.height + DEFAULT_END_HEIGHT_BLOCKS),
         start_height,
         description,
+        rejected_reason: None,
     };
     let key = state.poll_count.to_be_bytes();
     poll(deps.storage).save(&key, &new_poll)?;
@@ -220,7 +325,9 @@ pub fn create_poll(
             attr("action", "create_poll"),
             attr("creator", new_poll.creator),
             attr("poll_id", &poll_id),
-            attr("quorum_percentage", quorum_percentage.unwrap_or(0)),
+            attr("quorum", quorum),
+            attr("threshold", threshold),
+            attr("veto_threshold", veto_threshold),
             attr("end_height", new_poll.end_height),
             attr("start_height", start_height.unwrap_or(0)),
         ],
@@ -264,43 +371,55 @@ pub fn end_poll(
         });
     }
 
-    let mut no = 0u128;
-    let mut yes = 0u128;
-
-    for voter in &a_poll.voter_info {
-        if voter.vote == "yes" {
-            yes += voter.weight.u128();
-        } else {
-            no += voter.weight.u128();
-        }
-    }
-    let tallied_weight = yes + no;
+    let yes = a_poll.yes_votes;
+    // abstentions only count toward the quorum
+    let voted_weight = a_poll.yes_votes + a_poll.no_votes + a_poll.veto_votes;
+    let tallied_weight = if a_poll.options.is_empty() {
+        voted_weight + a_poll.abstain_votes
+    } else {
+        a_poll
+            .options
+            .iter()
+            .fold(Uint128::zero(), |total, option| total + option.votes)
+    };
 
     let mut rejected_reason = "";
     let mut passed = false;
 
-    if tallied_weight > 0 {
+    if !tallied_weight.is_zero() {
         let state = config_read(deps.storage).load()?;
+        let staked_weight = state.staked_tokens;
 
-        let staked_weight = deps
-            .querier
-            .query_balance(&env.contract.address, &state.denom)
-            .unwrap()
-            .amount
-            .u128();
-
-        if staked_weight == 0 {
+        if staked_weight.is_zero() {
             return Err(ContractError::PollNoStake {});
         }
 
-        let quorum = ((tallied_weight / staked_weight) * 100) as u8;
-        if a_poll.quorum_percentage.is_some() && quorum < a_poll.quorum_percentage.unwrap() {
-            // Quorum: More than quorum_percentage of the total staked tokens at the end of the voting
+        // Products with a Decimal are rounded down. As the votes are integers, a strict
+        // "greater than" against the rounded down product gives the same result as against
+        // the real product, so the quorum is checked on the stake that did not vote.
+        let missing_weight = staked_weight.saturating_sub(tallied_weight);
+        if missing_weight > staked_weight * (Decimal::one() - a_poll.quorum) {
+            // Quorum: At least quorum of the total staked tokens at the end of the voting
             // period need to have participated in the vote.
             rejected_reason = "Quorum not reached";
-        } else if yes > tallied_weight / 2 {
-            //Threshold: More than 50% of the tokens that participated in the vote
-            // (after excluding “Abstain” votes) need to have voted in favor of the proposal (“Yes”).
+        } else if !a_poll.options.is_empty() {
+            // Plurality: The option with the most votes wins, a tie for the most votes
+            // rejects the poll. The thresholds do not apply to multiple-choice polls.
+            match plurality(&a_poll.options) {
+                Some(option) => {
+                    a_poll.winning_option = Some(option);
+                    a_poll.status = PollStatus::Passed;
+                    passed = true;
+                }
+                None => rejected_reason = "Tie",
+            }
+        } else if a_poll.veto_votes > tallied_weight * a_poll.veto_threshold {
+            // Veto: More than veto_threshold of the tokens that participated in the vote
+            // rejects the poll, regardless of the other votes.
+            rejected_reason = "Vetoed";
+        } else if !voted_weight.is_zero() && yes > voted_weight * a_poll.threshold {
+            // Threshold: More than threshold of the tokens that participated in the vote
+            // (after excluding "Abstain" votes) need to have voted in favor of the proposal ("Yes").
             a_poll.status = PollStatus::Passed;
             passed = true;
         } else {
@@ -310,20 +429,31 @@ pub fn end_poll(
         rejected_reason = "Quorum not reached";
     }
     if !passed {
-        a_poll.status = PollStatus::Rejected
+        a_poll.status = PollStatus::Rejected;
+        a_poll.rejected_reason = Some(rejected_reason.to_string());
     }
+    // tokens locked in the poll are released on the next withdrawal of each voter
     poll(deps.storage).save(key, &a_poll)?;
 
-    for voter in &a_poll.voters {
-        unlock_tokens(deps.storage, voter, poll_id)?;
-    }
-
-    let attributes = vec![
+    let mut attributes = vec![
         attr("action", "end_poll"),
         attr("poll_id", &poll_id),
         attr("rejected_reason", rejected_reason),
         attr("passed", &passed),
     ];
+    match a_poll.winning_option {
+        Some(option) => attributes.push(attr(
+            "winning_option",
+            &a_poll.options[option as usize].name,
+        )),
+        None if !a_poll.options.is_empty() => attributes.push(attr("winning_option", "")),
+        None => attributes.extend(vec![
+            attr("yes_votes", a_poll.yes_votes),
+            attr("no_votes", a_poll.no_votes),
+            attr("abstain_votes", a_poll.abstain_votes),
+            attr("veto_votes", a_poll.veto_votes),
+        ]),
+    }
 
     let r = Response {
         submessages: vec![],
@@ -334,24 +464,35 @@ pub fn end_poll(
     Ok(r)
 }
 
-// unlock voter's tokens in a given poll
-fn unlock_tokens(
-    storage: &mut dyn Storage,
-    voter: &Addr,
-    poll_id: u64,
-) -> Result<Response, ContractError> {
-    let voter_key = &voter.as_str().as_bytes();
-    let mut token_manager = bank_read(storage).load(voter_key).unwrap();
+// finds the option with the most votes, None if several options share the most votes
+fn plurality(options: &[PollOption]) -> Option<u32> {
+    let most_votes = options.iter().map(|option| option.votes).max()?;
+    let mut leaders = options
+        .iter()
+        .enumerate()
+        .filter(|(_, option)| option.votes == most_votes);
+    match (leaders.next(), leaders.next()) {
+        (Some((index, _)), None) => Some(index as u32),
+        _ => None,
+    }
+}
 
+// unlock voter's tokens in polls that have ended
+fn unlock_tokens(storage: &dyn Storage, token_manager: &mut TokenManager) -> StdResult<()> {
     // unlock entails removing the mapped poll_id, retaining the rest
-    token_manager.locked_tokens.retain(|(k, _)| k != &poll_id);
-    bank(storage).save(voter_key, &token_manager)?;
-    Ok(Response::default())
+    let mut locked_tokens = vec![];
+    for (poll_id, weight) in token_manager.locked_tokens.drain(..) {
+        let a_poll = poll_read(storage).load(&poll_id.to_be_bytes())?;
+        if a_poll.status == PollStatus::InProgress {
+            locked_tokens.push((poll_id, weight));
+        }
+    }
+    token_manager.locked_tokens = locked_tokens;
+    Ok(())
 }
 
 // finds the largest locked amount in participated polls.
-fn locked_amount(voter: &[u8], storage: &dyn Storage) -> Uint128 {
-    let token_manager = bank_read(storage).load(voter).unwrap();
+fn locked_amount(token_manager: &TokenManager) -> Uint128 {
     token_manager
         .locked_tokens
         .iter()
@@ -360,56 +501,86 @@ fn locked_amount(voter: &[u8], storage: &dyn Storage) -> Uint128 {
         .unwrap_or_default()
 }
 
-fn has_voted(voter: &Addr, a_poll: &Poll) -> bool {
-    a_poll.voters.iter().any(|i| i == voter)
-}
-
 pub fn cast_vote(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     poll_id: u64,
-    vote: String,
+    vote: VoteOption,
     weight: Uint128,
 ) -> Result<Response, ContractError> {
-    let poll_key = &poll_id.to_be_bytes();
-    let state = config_read(deps.storage).load()?;
-    if poll_id == 0 || state.poll_count > poll_id {
-        return Err(ContractError::PollNotExist {});
+    let mut a_poll = load_open_poll(deps.storage, poll_id, &info.sender)?;
+    if !a_poll.options.is_empty() {
+        return Err(ContractError::PollVoteMismatch {});
     }
-
-    let mut a_poll = poll(deps.storage).load(poll_key)?;
-
-    if a_poll.status != PollStatus::InProgress {
-        return Err(ContractError::PollNotInProgress {});
+    if weight.is_zero() {
+        return Err(ContractError::PollZeroWeight {});
     }
-
-    if has_voted(&info.sender, &a_poll) {
-        return Err(ContractError::PollSenderVoted {});
+    lock_tokens(deps.storage, &info.sender, poll_id, weight)?;
+
+    // keep running totals, so ending the poll does not need to visit every voter
+    match vote {
+        VoteOption::Yes => a_poll.yes_votes += weight,
+        VoteOption::No => a_poll.no_votes += weight,
+        VoteOption::Abstain => a_poll.abstain_votes += weight,
+        VoteOption::NoWithVeto => a_poll.veto_votes += weight,
     }
+    poll(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
 
-    let key = info.sender.as_str().as_bytes();
-    let mut token_manager = bank_read(deps.storage).may_load(key)?.unwrap_or_default();
+    let voter_info = Voter {
+        vote: Some(vote),
+        choices: vec![],
+        weight,
+    };
+    save_vote(deps.storage, poll_id, &info.sender, &voter_info)
+}
 
-    if token_manager.token_balance < weight {
-        return Err(ContractError::PollInsufficientStake {});
+pub fn cast_choice_vote(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+    choices: Vec<Choice>,
+) -> Result<Response, ContractError> {
+    let mut a_poll = load_open_poll(deps.storage, poll_id, &info.sender)?;
+    if a_poll.options.is_empty() {
+        return Err(ContractError::PollVoteMismatch {});
     }
-    token_manager.participated_polls.push(poll_id);
-    token_manager.locked_tokens.push((poll_id, weight));
-    bank(deps.storage).save(key, &token_manager)?;
+    validate_choices(&a_poll, &choices)?;
 
-    a_poll.voters.push(info.sender.clone());
+    let weight = choices.iter().try_fold(Uint128::zero(), |total, choice| {
+        total.checked_add(choice.weight)
+    })?;
+    lock_tokens(deps.storage, &info.sender, poll_id, weight)?;
 
-    let voter_info = Voter { vote, weight };
+    for choice in &choices {
+        let option = &mut a_poll.options[choice.option as usize];
+        option.votes = option.votes.checked_add(choice.weight)?;
+    }
+    poll(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
 
-    a_poll.voter_info.push(voter_info);
-    poll(deps.storage).save(poll_key, &a_poll)?;
+    let voter_info = Voter {
+        vote: None,
+        choices,
+        weight,
+    };
+    save_vote(deps.storage, poll_id, &info.sender, &voter_info)
+}
+
+// stores the vote of a voter and reports it
+fn save_vote(
+    storage: &mut dyn Storage,
+    poll_id: u64,
+    voter: &Addr,
+    voter_info: &Voter,
+) -> Result<Response, ContractError> {
+    poll_voters(storage, poll_id).save(voter.as_str().as_bytes(), voter_info)?;
 
     let attributes = vec![
         attr("action", "vote_casted"),
-        attr("poll_id", &poll_id),
-        attr("weight", &weight),
-        attr("voter", &info.sender),
+        attr("poll_id", poll_id),
+        attr("weight", voter_info.weight),
+        attr("voter", voter),
     ];
 
     let r = Response {
@@ -421,6 +592,72 @@ pub fn cast_vote(
     Ok(r)
 }
 
+/// validate_choices returns an error if the choices do not fit the options and mode of the poll
+/// (we require existing, distinct options with a positive weight, a single one in single choice mode)
+fn validate_choices(a_poll: &Poll, choices: &[Choice]) -> Result<(), ContractError> {
+    let max_choices = match a_poll.mode {
+        PollMode::SingleChoice => 1,
+        PollMode::WeightedSplit => a_poll.options.len(),
+    };
+    let invalid = choices.is_empty()
+        || choices.len() > max_choices
+        || choices.iter().enumerate().any(|(i, choice)| {
+            choice.option as usize >= a_poll.options.len()
+                || choice.weight.is_zero()
+                || choices[..i].iter().any(|c| c.option == choice.option)
+        });
+    if invalid {
+        Err(ContractError::PollInvalidChoice {})
+    } else {
+        Ok(())
+    }
+}
+
+// loads a poll the sender can still vote in
+fn load_open_poll(
+    storage: &dyn Storage,
+    poll_id: u64,
+    voter: &Addr,
+) -> Result<Poll, ContractError> {
+    let state = config_read(storage).load()?;
+    if poll_id == 0 || poll_id > state.poll_count {
+        return Err(ContractError::PollNotExist {});
+    }
+
+    let a_poll = poll_read(storage).load(&poll_id.to_be_bytes())?;
+
+    if a_poll.status != PollStatus::InProgress {
+        return Err(ContractError::PollNotInProgress {});
+    }
+
+    if poll_voters_read(storage, poll_id)
+        .may_load(voter.as_str().as_bytes())?
+        .is_some()
+    {
+        return Err(ContractError::PollSenderVoted {});
+    }
+    Ok(a_poll)
+}
+
+// locks the weight of a vote in the voter's stake
+fn lock_tokens(
+    storage: &mut dyn Storage,
+    voter: &Addr,
+    poll_id: u64,
+    weight: Uint128,
+) -> Result<(), ContractError> {
+    let key = voter.as_str().as_bytes();
+    let mut token_manager = bank_read(storage).may_load(key)?.unwrap_or_default();
+
+    if token_manager.token_balance < weight {
+        return Err(ContractError::PollInsufficientStake {});
+    }
+    token_manager.participated_polls.push(poll_id);
+    token_manager.locked_tokens.push((poll_id, weight));
+    bank(storage).save(key, &token_manager)?;
+    Ok(())
+}
+
 fn send_tokens(to_address: &Addr, amount: Vec<Coin>, action: &str) -> Response {
     let attributes = vec![attr("action", action), attr("to", to_address.clone())];
 
@@ -443,6 +680,11 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             token_balance(deps, deps.api.addr_validate(address.as_str())?)
         }
         QueryMsg::Poll { poll_id } => query_poll(deps, poll_id),
+        QueryMsg::Voters {
+            poll_id,
+            start_after,
+            limit,
+        } => query_voters(deps, poll_id, start_after, limit),
     }
 }
 
@@ -458,14 +700,62 @@ fn query_poll(deps: Deps, poll_id: u64) -> StdResult<Binary> {
     let resp = PollResponse {
         creator: poll.creator.to_string(),
         status: poll.status,
-        quorum_percentage: poll.quorum_percentage,
+        quorum: poll.quorum,
+        threshold: poll.threshold,
+        veto_threshold: poll.veto_threshold,
         end_height: Some(poll.end_height),
         start_height: poll.start_height,
         description: poll.description,
+        yes_votes: poll.yes_votes,
+        no_votes: poll.no_votes,
+        abstain_votes: poll.abstain_votes,
+        veto_votes: poll.veto_votes,
+        options: poll.options,
+        mode: poll.mode,
+        winning_option: poll.winning_option,
+        rejected_reason: poll.rejected_reason,
     };
     to_binary(&resp)
 }
 
+fn query_voters(
+    deps: Deps,
+    poll_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    if poll_read(deps.storage)
+        .may_load(&poll_id.to_be_bytes())?
+        .is_none()
+    {
+        return Err(StdError::generic_err("Poll does not exist"));
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // the range is inclusive, so start right after the last voter returned
+    let start = start_after.map(|voter| {
+        let mut start = voter.into_bytes();
+        start.push(0);
+        start
+    });
+
+    let voters = poll_voters_read(deps.storage, poll_id)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, voter) = item?;
+            Ok(VoterInfo {
+                voter: String::from_utf8(key)?,
+                vote: voter.vote,
+                choices: voter.choices,
+                weight: voter.weight,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&VotersResponse { voters })
+}
+
 fn token_balance(deps: Deps, address: Addr) -> StdResult<Binary> {
     let token_manager = bank_read(deps.storage)
         .may_load(address.as_str().as_bytes())?
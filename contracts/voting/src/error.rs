@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cosmwasm_std::{Decimal, OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -48,6 +48,30 @@ pub enum ContractError {
     #[error("sender staked tokens insufficient")]
     PollInsufficientStake {},
 
-    #[error("quorum percentage must be 0 to 100 (quorum_percentage: {quorum_percentage})")]
-    PollQuorumPercentageMismatch { quorum_percentage: u8 },
+    #[error("vote weight must be positive")]
+    PollZeroWeight {},
+
+    #[error("quorum must be 0 to 100 percent (quorum: {quorum})")]
+    PollQuorumOutOfRange { quorum: Decimal },
+
+    #[error("threshold must be 0 to 100 percent (threshold: {threshold})")]
+    PollThresholdOutOfRange { threshold: Decimal },
+
+    #[error("poll needs {min_options} to {max_options} distinct, non-empty options")]
+    PollInvalidOptions {
+        min_options: usize,
+        max_options: usize,
+    },
+
+    #[error("vote does not match the type of the poll")]
+    PollVoteMismatch {},
+
+    #[error("invalid choice of poll options")]
+    PollInvalidChoice {},
+
+    #[error("poll mode requires options")]
+    PollModeWithoutOptions {},
+
+    #[error("veto threshold must be 0 to 100 percent (veto_threshold: {veto_threshold})")]
+    PollVetoThresholdOutOfRange { veto_threshold: Decimal },
 }
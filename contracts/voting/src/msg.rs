@@ -1,11 +1,17 @@
-use crate::state::PollStatus;
-use cosmwasm_std::Uint128;
+use crate::state::{Choice, PollMode, PollOption, PollStatus, VoteOption};
+use cosmwasm_std::{Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub denom: String,
+    /// default quorum of polls, 0% if not set
+    pub quorum: Option<Decimal>,
+    /// default pass threshold of polls, 50% if not set
+    pub threshold: Option<Decimal>,
+    /// default veto threshold of polls, 33.4% if not set
+    pub veto_threshold: Option<Decimal>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -13,18 +19,29 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     CastVote {
         poll_id: u64,
-        vote: String,
+        vote: VoteOption,
         weight: Uint128,
     },
+    /// Votes in a multiple-choice poll, the weight of the vote is the sum of the choices
+    CastChoiceVote {
+        poll_id: u64,
+        choices: Vec<Choice>,
+    },
     StakeVotingTokens {},
     WithdrawVotingTokens {
         amount: Option<Uint128>,
     },
     CreatePoll {
-        quorum_percentage: Option<u8>,
+        quorum: Option<Decimal>,
+        threshold: Option<Decimal>,
+        veto_threshold: Option<Decimal>,
         description: String,
         start_height: Option<u64>,
         end_height: Option<u64>,
+        /// makes a multiple-choice poll, decided by plurality instead of the thresholds
+        options: Option<Vec<String>>,
+        /// how votes are cast in a multiple-choice poll, single choice if not set
+        mode: Option<PollMode>,
     },
     EndPoll {
         poll_id: u64,
@@ -35,31 +52,63 @@ pub enum ExecuteMsg {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
-    TokenStake { address: String },
-    Poll { poll_id: u64 },
+    TokenStake {
+        address: String,
+    },
+    Poll {
+        poll_id: u64,
+    },
+    Voters {
+        poll_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct PollResponse {
     pub creator: String,
     pub status: PollStatus,
-    pub quorum_percentage: Option<u8>,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub veto_threshold: Decimal,
     pub end_height: Option<u64>,
     pub start_height: Option<u64>,
     pub description: String,
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub abstain_votes: Uint128,
+    pub veto_votes: Uint128,
+    pub options: Vec<PollOption>,
+    pub mode: PollMode,
+    pub winning_option: Option<u32>,
+    pub rejected_reason: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoterInfo {
+    pub voter: String,
+    pub vote: Option<VoteOption>,
+    pub choices: Vec<Choice>,
+    pub weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotersResponse {
+    pub voters: Vec<VoterInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CreatePollResponse {
     pub poll_id: u64,
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PollCountResponse {
     pub poll_count: u64,
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TokenStakeResponse {
     pub token_balance: Uint128,
 }
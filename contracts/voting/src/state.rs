@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_std::{Addr, Decimal, Storage, Uint128};
 use cosmwasm_storage::{
     bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
     Singleton,
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 static CONFIG_KEY: &[u8] = b"config";
 static POLL_KEY: &[u8] = b"polls";
 static BANK_KEY: &[u8] = b"bank";
+static POLL_VOTERS_KEY: &[u8] = b"poll_voters";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
@@ -16,6 +17,12 @@ pub struct State {
     pub owner: Addr,
     pub poll_count: u64,
     pub staked_tokens: Uint128,
+    /// default quorum of new polls
+    pub quorum: Decimal,
+    /// default pass threshold of new polls
+    pub threshold: Decimal,
+    /// default veto threshold of new polls
+    pub veto_threshold: Decimal,
 }
 
 #[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -27,10 +34,47 @@ pub struct TokenManager {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Voter {
-    pub vote: String,
+    /// vote in a yes/no poll
+    pub vote: Option<VoteOption>,
+    /// choices in a multiple-choice poll
+    #[serde(default)]
+    pub choices: Vec<Choice>,
     pub weight: Uint128,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Choice {
+    /// index of the option in the poll
+    pub option: u32,
+    pub weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollMode {
+    /// a voter puts their whole weight on a single option
+    SingleChoice,
+    /// a voter divides their weight across options
+    WeightedSplit,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollOption {
+    pub name: String,
+    pub votes: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Yes,
+    No,
+    /// counts toward the quorum, but not the threshold
+    Abstain,
+    /// counts as no, and rejects the poll if more than the veto threshold voted it
+    NoWithVeto,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum PollStatus {
     InProgress,
@@ -43,14 +87,26 @@ pub enum PollStatus {
 pub struct Poll {
     pub creator: Addr,
     pub status: PollStatus,
-    pub quorum_percentage: Option<u8>,
+    /// share of the staked tokens that has to take part in the vote
+    pub quorum: Decimal,
+    /// share of the tallied votes, without abstentions, that has to be in favor, exclusive
+    pub threshold: Decimal,
+    /// share of the tallied votes that vetoes the poll, exclusive
+    pub veto_threshold: Decimal,
     pub yes_votes: Uint128,
     pub no_votes: Uint128,
-    pub voters: Vec<Addr>,
-    pub voter_info: Vec<Voter>,
+    pub abstain_votes: Uint128,
+    pub veto_votes: Uint128,
+    /// options of a multiple-choice poll, empty for yes/no polls
+    pub options: Vec<PollOption>,
+    pub mode: PollMode,
+    /// index of the option that won a multiple-choice poll
+    pub winning_option: Option<u32>,
     pub end_height: u64,
     pub start_height: Option<u64>,
     pub description: String,
+    /// set when the poll is rejected
+    pub rejected_reason: Option<String>,
 }
 
 pub fn config(storage: &mut dyn Storage) -> Singleton<State> {
@@ -69,6 +125,15 @@ pub fn poll_read(storage: &dyn Storage) -> ReadonlyBucket<Poll> {
     bucket_read(storage, POLL_KEY)
 }
 
+/// Votes cast in a poll, keyed by voter
+pub fn poll_voters<'a>(storage: &'a mut dyn Storage, poll_id: u64) -> Bucket<'a, Voter> {
+    Bucket::multilevel(storage, &[POLL_VOTERS_KEY, &poll_id.to_be_bytes()])
+}
+
+pub fn poll_voters_read<'a>(storage: &'a dyn Storage, poll_id: u64) -> ReadonlyBucket<'a, Voter> {
+    ReadonlyBucket::multilevel(storage, &[POLL_VOTERS_KEY, &poll_id.to_be_bytes()])
+}
+
 pub fn bank(storage: &mut dyn Storage) -> Bucket<TokenManager> {
     bucket(storage, BANK_KEY)
 }
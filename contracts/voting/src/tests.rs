@@ -2,12 +2,14 @@
 mod tests {
     use crate::contract::{execute, instantiate, query, VOTING_TOKEN};
     use crate::error::ContractError;
-    use crate::msg::{ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg};
-    use crate::state::{config_read, PollStatus, State};
+    use crate::msg::{
+        ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, VoterInfo, VotersResponse,
+    };
+    use crate::state::{config_read, Choice, PollMode, PollOption, PollStatus, State, VoteOption};
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::{
-        attr, coins, from_binary, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo,
-        Response, StdError, Timestamp, Uint128,
+        attr, coins, from_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env,
+        MessageInfo, Response, StdError, Timestamp, Uint128,
     };
 
     const DEFAULT_END_HEIGHT: u64 = 100800u64;
@@ -18,6 +20,9 @@ mod tests {
     fn mock_instantiate(deps: DepsMut) {
         let msg = InstantiateMsg {
             denom: String::from(VOTING_TOKEN),
+            quorum: None,
+            threshold: None,
+            veto_threshold: None,
         };
 
         let info = mock_info(TEST_CREATOR, &coins(2, &msg.denom));
@@ -36,6 +41,9 @@ mod tests {
     fn init_msg() -> InstantiateMsg {
         InstantiateMsg {
             denom: String::from(VOTING_TOKEN),
+            quorum: None,
+            threshold: None,
+            veto_threshold: None,
         }
     }
 
@@ -56,6 +64,9 @@ mod tests {
                 owner: Addr::unchecked(TEST_CREATOR),
                 poll_count: 0,
                 staked_tokens: Uint128::zero(),
+                quorum: Decimal::zero(),
+                threshold: Decimal::percent(50),
+                veto_threshold: default_veto_threshold(),
             }
         );
     }
@@ -86,8 +97,8 @@ mod tests {
 
         match res {
             Ok(_) => panic!("Must return error"),
-            Err(ContractError::PollQuorumPercentageMismatch { quorum_percentage }) => {
-                assert_eq!(quorum_percentage, qp)
+            Err(ContractError::PollQuorumOutOfRange { quorum }) => {
+                assert_eq!(quorum, Decimal::percent(qp))
             }
             Err(e) => panic!("Unexpected error: {:?}", e),
         }
@@ -121,16 +132,20 @@ mod tests {
     }
 
     fn create_poll_msg(
-        quorum_percentage: u8,
+        quorum_percentage: u64,
         description: String,
         start_height: Option<u64>,
         end_height: Option<u64>,
     ) -> ExecuteMsg {
         let msg = ExecuteMsg::CreatePoll {
-            quorum_percentage: Some(quorum_percentage),
+            quorum: Some(Decimal::percent(quorum_percentage)),
+            threshold: None,
+            veto_threshold: None,
             description,
             start_height,
             end_height,
+            options: None,
+            mode: None,
         };
         msg
     }
@@ -263,7 +278,7 @@ mod tests {
 
         let msg = ExecuteMsg::CastVote {
             poll_id: 1,
-            vote: "yes".to_string(),
+            vote: VoteOption::Yes,
             weight: Uint128::from(stake_amount),
         };
         let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
@@ -297,6 +312,10 @@ mod tests {
                 attr("poll_id", "1"),
                 attr("rejected_reason", ""),
                 attr("passed", "true"),
+                attr("yes_votes", "1000"),
+                attr("no_votes", "0"),
+                attr("abstain_votes", "0"),
+                attr("veto_votes", "0"),
             ]
         );
         let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
@@ -326,6 +345,10 @@ mod tests {
                 attr("poll_id", "1"),
                 attr("rejected_reason", "Quorum not reached"),
                 attr("passed", "false"),
+                attr("yes_votes", "0"),
+                attr("no_votes", "0"),
+                attr("abstain_votes", "0"),
+                attr("veto_votes", "0"),
             ]
         );
 
@@ -361,7 +384,9 @@ mod tests {
                 attr("action", "create_poll"),
                 attr("creator", TEST_CREATOR),
                 attr("poll_id", "1"),
-                attr("quorum_percentage", "30"),
+                attr("quorum", "0.3"),
+                attr("threshold", "0.5"),
+                attr("veto_threshold", "0.334"),
                 attr("end_height", "1"),
                 attr("start_height", "0"),
             ]
@@ -376,7 +401,7 @@ mod tests {
 
         let msg = ExecuteMsg::CastVote {
             poll_id: 1,
-            vote: "yes".to_string(),
+            vote: VoteOption::Yes,
             weight: Uint128::from(10u128),
         };
         let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
@@ -409,6 +434,10 @@ mod tests {
                 attr("poll_id", "1"),
                 attr("rejected_reason", "Quorum not reached"),
                 attr("passed", "false"),
+                attr("yes_votes", "10"),
+                attr("no_votes", "0"),
+                attr("abstain_votes", "0"),
+                attr("veto_votes", "0"),
             ]
         );
 
@@ -446,7 +475,9 @@ mod tests {
                 attr("action", "create_poll"),
                 attr("creator", TEST_CREATOR),
                 attr("poll_id", "1"),
-                attr("quorum_percentage", "10"),
+                attr("quorum", "0.1"),
+                attr("threshold", "0.5"),
+                attr("veto_threshold", "0.334"),
                 attr("end_height", "1"),
                 attr("start_height", "0"),
             ]
@@ -472,7 +503,7 @@ mod tests {
         let (env, info) = mock_info_height(TEST_VOTER_2, &[], 0, 0);
         let msg = ExecuteMsg::CastVote {
             poll_id: 1,
-            vote: "no".to_string(),
+            vote: VoteOption::No,
             weight: Uint128::from(voter2_stake),
         };
         let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
@@ -495,6 +526,10 @@ mod tests {
                 attr("poll_id", "1"),
                 attr("rejected_reason", "Threshold not reached"),
                 attr("passed", "false"),
+                attr("yes_votes", "0"),
+                attr("no_votes", "1000"),
+                attr("abstain_votes", "0"),
+                attr("veto_votes", "0"),
             ]
         );
 
@@ -563,7 +598,7 @@ mod tests {
         let info = mock_info(TEST_VOTER, &coins(11, VOTING_TOKEN));
         let msg = ExecuteMsg::CastVote {
             poll_id: 1,
-            vote: "yes".to_string(),
+            vote: VoteOption::Yes,
             weight: Uint128::from(1u128),
         };
 
@@ -608,7 +643,7 @@ mod tests {
         let weight = 10u128;
         let msg = ExecuteMsg::CastVote {
             poll_id: 1,
-            vote: "yes".to_string(),
+            vote: VoteOption::Yes,
             weight: Uint128::from(weight),
         };
 
@@ -635,6 +670,9 @@ mod tests {
                 owner: Addr::unchecked(TEST_CREATOR),
                 poll_count: 0,
                 staked_tokens: Uint128::from(11u128),
+                quorum: Decimal::zero(),
+                threshold: Decimal::percent(50),
+                veto_threshold: default_veto_threshold(),
             }
         );
 
@@ -662,6 +700,9 @@ mod tests {
                 owner: Addr::unchecked(TEST_CREATOR),
                 poll_count: 0,
                 staked_tokens: Uint128::zero(),
+                quorum: Decimal::zero(),
+                threshold: Decimal::percent(50),
+                veto_threshold: default_veto_threshold(),
             }
         );
     }
@@ -742,7 +783,7 @@ mod tests {
         let weight = 1u128;
         let msg = ExecuteMsg::CastVote {
             poll_id: 1,
-            vote: "yes".to_string(),
+            vote: VoteOption::Yes,
             weight: Uint128::from(weight),
         };
         let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
@@ -750,7 +791,7 @@ mod tests {
 
         let msg = ExecuteMsg::CastVote {
             poll_id: 1,
-            vote: "yes".to_string(),
+            vote: VoteOption::Yes,
             weight: Uint128::from(weight),
         };
         let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
@@ -769,7 +810,7 @@ mod tests {
 
         let msg = ExecuteMsg::CastVote {
             poll_id: 0,
-            vote: "yes".to_string(),
+            vote: VoteOption::Yes,
             weight: Uint128::from(1u128),
         };
         let info = mock_info(TEST_VOTER, &coins(11, VOTING_TOKEN));
@@ -783,6 +824,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn fails_cast_vote_zero_weight() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+
+        let (env, info) = mock_info_height(TEST_CREATOR, &coins(2, VOTING_TOKEN), 0, 10000);
+        let msg = create_poll_msg(0, "test".to_string(), None, None);
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let msg = ExecuteMsg::StakeVotingTokens {};
+        let info = mock_info(TEST_VOTER, &coins(11, VOTING_TOKEN));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info(TEST_VOTER, &[]);
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: VoteOption::Yes,
+            weight: Uint128::zero(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::PollZeroWeight {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn cast_vote_on_older_poll() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+
+        for _ in 0..2 {
+            let (env, info) = mock_info_height(TEST_CREATOR, &coins(2, VOTING_TOKEN), 0, 10000);
+            let msg = create_poll_msg(0, "test".to_string(), None, None);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+        }
+
+        let msg = ExecuteMsg::StakeVotingTokens {};
+        let info = mock_info(TEST_VOTER, &coins(11, VOTING_TOKEN));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // the first poll is still open while the second one runs
+        let info = mock_info(TEST_VOTER, &[]);
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: VoteOption::Yes,
+            weight: Uint128::from(10u128),
+        };
+        let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_cast_vote_success(TEST_VOTER, 10, 1, execute_res);
+
+        let info = mock_info(TEST_VOTER, &[]);
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 3,
+            vote: VoteOption::Yes,
+            weight: Uint128::from(10u128),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::PollNotExist {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
     #[test]
     fn happy_days_stake_voting_tokens() {
         let mut deps = mock_dependencies(&[]);
@@ -842,10 +947,864 @@ mod tests {
         }
     }
 
+    #[test]
+    fn query_voters_paginated() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+
+        let (env, info) = mock_info_height(TEST_CREATOR, &coins(2, VOTING_TOKEN), 0, 10000);
+        let msg = create_poll_msg(30, "test".to_string(), None, None);
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        for (voter, vote, weight) in [
+            (TEST_VOTER, VoteOption::Yes, 10u128),
+            (TEST_VOTER_2, VoteOption::No, 5u128),
+            ("voter3", VoteOption::Yes, 7u128),
+        ] {
+            let info = mock_info(voter, &coins(weight, VOTING_TOKEN));
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::StakeVotingTokens {},
+            )
+            .unwrap();
+            let msg = ExecuteMsg::CastVote {
+                poll_id: 1,
+                vote,
+                weight: Uint128::from(weight),
+            };
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // totals are kept up to date on every vote
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+        let value: PollResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(17u128), value.yes_votes);
+        assert_eq!(Uint128::from(5u128), value.no_votes);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Voters {
+                poll_id: 1,
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+        let value: VotersResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.voters,
+            vec![
+                VoterInfo {
+                    voter: TEST_VOTER.to_string(),
+                    vote: Some(VoteOption::Yes),
+                    choices: vec![],
+                    weight: Uint128::from(10u128),
+                },
+                VoterInfo {
+                    voter: TEST_VOTER_2.to_string(),
+                    vote: Some(VoteOption::No),
+                    choices: vec![],
+                    weight: Uint128::from(5u128),
+                },
+            ]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Voters {
+                poll_id: 1,
+                start_after: Some(TEST_VOTER_2.to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: VotersResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.voters,
+            vec![VoterInfo {
+                voter: "voter3".to_string(),
+                vote: Some(VoteOption::Yes),
+                choices: vec![],
+                weight: Uint128::from(7u128),
+            }]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Voters {
+                poll_id: 2,
+                start_after: None,
+                limit: None,
+            },
+        );
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Poll does not exist"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+            _ => panic!("Must return error"),
+        }
+    }
+
+    #[test]
+    fn withdraw_unlocks_tokens_of_ended_poll() {
+        let stake_amount = 1000;
+
+        let mut deps = mock_dependencies(&coins(stake_amount, VOTING_TOKEN));
+        mock_instantiate(deps.as_mut());
+        let (mut creator_env, creator_info) =
+            mock_info_height(TEST_CREATOR, &coins(2, VOTING_TOKEN), 1000, 10000);
+
+        let msg = create_poll_msg(0, "test".to_string(), None, Some(1001));
+        execute(
+            deps.as_mut(),
+            creator_env.clone(),
+            creator_info.clone(),
+            msg,
+        )
+        .unwrap();
+
+        let info = mock_info(TEST_VOTER, &coins(stake_amount, VOTING_TOKEN));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::StakeVotingTokens {},
+        )
+        .unwrap();
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: VoteOption::Yes,
+            weight: Uint128::from(stake_amount),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // the vote keeps the tokens locked while the poll is in progress
+        let msg = ExecuteMsg::WithdrawVotingTokens { amount: None };
+        match execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::ExcessiveWithdraw { max_amount }) => {
+                assert_eq!(max_amount, Uint128::zero())
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        creator_env.block.height = 1001;
+        let end_msg = ExecuteMsg::EndPoll { poll_id: 1 };
+        execute(deps.as_mut(), creator_env, creator_info, end_msg).unwrap();
+
+        let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            execute_res.messages.first().expect("no message"),
+            &CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_VOTER.to_string(),
+                amount: coins(stake_amount, VOTING_TOKEN),
+            })
+        );
+    }
+
+    #[test]
+    fn create_poll_uses_config_defaults() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            denom: String::from(VOTING_TOKEN),
+            quorum: Some(Decimal::percent(20)),
+            threshold: Some(Decimal::percent(66)),
+            veto_threshold: None,
+        };
+        let info = mock_info(TEST_CREATOR, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            quorum: None,
+            threshold: None,
+            veto_threshold: None,
+            description: "test".to_string(),
+            start_height: None,
+            end_height: None,
+            options: None,
+            mode: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            quorum: Some(Decimal::percent(1)),
+            threshold: Some(Decimal::percent(90)),
+            veto_threshold: None,
+            description: "test".to_string(),
+            start_height: None,
+            end_height: None,
+            options: None,
+            mode: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+        let value: PollResponse = from_binary(&res).unwrap();
+        assert_eq!(Decimal::percent(20), value.quorum);
+        assert_eq!(Decimal::percent(66), value.threshold);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 2 }).unwrap();
+        let value: PollResponse = from_binary(&res).unwrap();
+        assert_eq!(Decimal::percent(1), value.quorum);
+        assert_eq!(Decimal::percent(90), value.threshold);
+    }
+
+    #[test]
+    fn fails_invalid_threshold() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            denom: String::from(VOTING_TOKEN),
+            quorum: None,
+            threshold: Some(Decimal::percent(101)),
+            veto_threshold: None,
+        };
+        let info = mock_info(TEST_CREATOR, &[]);
+        match instantiate(deps.as_mut(), mock_env(), info.clone(), msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::PollThresholdOutOfRange { threshold }) => {
+                assert_eq!(threshold, Decimal::percent(101))
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        mock_instantiate(deps.as_mut());
+        let msg = ExecuteMsg::CreatePoll {
+            quorum: None,
+            threshold: Some(Decimal::percent(101)),
+            veto_threshold: None,
+            description: "test".to_string(),
+            start_height: None,
+            end_height: None,
+            options: None,
+            mode: None,
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::PollThresholdOutOfRange { threshold }) => {
+                assert_eq!(threshold, Decimal::percent(101))
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn end_poll_quorum_boundaries() {
+        // exactly at the quorum is enough
+        let res = end_poll_with_votes(
+            Decimal::percent(30),
+            Decimal::percent(50),
+            None,
+            &[(VoteOption::Yes, 30), (VoteOption::No, 0)],
+            70,
+        );
+        assert_end_poll_result(res, true, "");
+
+        let res = end_poll_with_votes(
+            Decimal::percent(30),
+            Decimal::percent(50),
+            None,
+            &[(VoteOption::Yes, 29), (VoteOption::No, 0)],
+            71,
+        );
+        assert_end_poll_result(res, false, "Quorum not reached");
+
+        // small shares are not truncated to zero: 1 of 3 is 33.3..%
+        let res = end_poll_with_votes(
+            Decimal::percent(33),
+            Decimal::percent(50),
+            None,
+            &[(VoteOption::Yes, 1), (VoteOption::No, 0)],
+            2,
+        );
+        assert_end_poll_result(res, true, "");
+
+        let res = end_poll_with_votes(
+            Decimal::percent(34),
+            Decimal::percent(50),
+            None,
+            &[(VoteOption::Yes, 1), (VoteOption::No, 0)],
+            2,
+        );
+        assert_end_poll_result(res, false, "Quorum not reached");
+
+        let res = end_poll_with_votes(
+            Decimal::one(),
+            Decimal::percent(50),
+            None,
+            &[(VoteOption::Yes, 3), (VoteOption::No, 2)],
+            0,
+        );
+        assert_end_poll_result(res, true, "");
+
+        // large stakes do not overflow
+        let res = end_poll_with_votes(
+            Decimal::percent(30),
+            Decimal::percent(50),
+            None,
+            &[
+                (VoteOption::Yes, 3_000_000_000_000_000_000_000),
+                (VoteOption::No, 0),
+            ],
+            7_000_000_000_000_000_000_000,
+        );
+        assert_end_poll_result(res, true, "");
+
+        let res = end_poll_with_votes(
+            Decimal::percent(30),
+            Decimal::percent(50),
+            None,
+            &[
+                (VoteOption::Yes, 2_999_999_999_999_999_999_999),
+                (VoteOption::No, 0),
+            ],
+            7_000_000_000_000_000_000_001,
+        );
+        assert_end_poll_result(res, false, "Quorum not reached");
+    }
+
+    #[test]
+    fn end_poll_threshold_boundaries() {
+        // exactly at the threshold is not enough
+        let res = end_poll_with_votes(
+            Decimal::zero(),
+            Decimal::percent(60),
+            None,
+            &[(VoteOption::Yes, 60), (VoteOption::No, 40)],
+            0,
+        );
+        assert_end_poll_result(res, false, "Threshold not reached");
+
+        let res = end_poll_with_votes(
+            Decimal::zero(),
+            Decimal::percent(60),
+            None,
+            &[(VoteOption::Yes, 61), (VoteOption::No, 39)],
+            0,
+        );
+        assert_end_poll_result(res, true, "");
+
+        // 2 of 3 is 66.6..%, just above 66%, rejected by 67%
+        let res = end_poll_with_votes(
+            Decimal::zero(),
+            Decimal::percent(66),
+            None,
+            &[(VoteOption::Yes, 2), (VoteOption::No, 1)],
+            0,
+        );
+        assert_end_poll_result(res, true, "");
+
+        let res = end_poll_with_votes(
+            Decimal::zero(),
+            Decimal::percent(67),
+            None,
+            &[(VoteOption::Yes, 2), (VoteOption::No, 1)],
+            0,
+        );
+        assert_end_poll_result(res, false, "Threshold not reached");
+
+        // the default threshold is 50%, a tie is rejected
+        let res = end_poll_with_votes(
+            Decimal::zero(),
+            Decimal::percent(50),
+            None,
+            &[(VoteOption::Yes, 5), (VoteOption::No, 5)],
+            0,
+        );
+        assert_end_poll_result(res, false, "Threshold not reached");
+    }
+
+    #[test]
+    fn end_poll_abstain_counts_toward_quorum_only() {
+        // abstentions reach the quorum of 50%
+        let votes = [(VoteOption::Yes, 20), (VoteOption::Abstain, 40)];
+        let res = end_poll_with_votes(Decimal::percent(50), Decimal::percent(50), None, &votes, 40);
+        assert_end_poll_result(res, true, "");
+
+        // but do not count against the threshold: 20 yes of 35 voted
+        let votes = [
+            (VoteOption::Yes, 20),
+            (VoteOption::No, 15),
+            (VoteOption::Abstain, 65),
+        ];
+        let res = end_poll_with_votes(Decimal::percent(50), Decimal::percent(50), None, &votes, 0);
+        assert_end_poll_result(res, true, "");
+
+        // a poll with only abstentions does not pass
+        let votes = [(VoteOption::Abstain, 100)];
+        let res = end_poll_with_votes(Decimal::zero(), Decimal::zero(), None, &votes, 0);
+        assert_end_poll_result(res, false, "Threshold not reached");
+    }
+
+    #[test]
+    fn end_poll_vetoed() {
+        // the veto counts against the total of the votes, including abstentions
+        let votes = [
+            (VoteOption::Yes, 60),
+            (VoteOption::NoWithVeto, 34),
+            (VoteOption::Abstain, 6),
+        ];
+        let res = end_poll_with_votes(Decimal::zero(), Decimal::percent(50), None, &votes, 0);
+        assert_end_poll_result(res.clone(), false, "Vetoed");
+        assert_eq!(
+            res.attributes[4..].to_vec(),
+            vec![
+                attr("yes_votes", "60"),
+                attr("no_votes", "0"),
+                attr("abstain_votes", "6"),
+                attr("veto_votes", "34"),
+            ]
+        );
+
+        // exactly at the veto threshold does not veto
+        let votes = [(VoteOption::Yes, 60), (VoteOption::NoWithVeto, 40)];
+        let res = end_poll_with_votes(
+            Decimal::zero(),
+            Decimal::percent(50),
+            Some(Decimal::percent(40)),
+            &votes,
+            0,
+        );
+        assert_end_poll_result(res, true, "");
+
+        // below the veto threshold, vetoes count as no
+        let votes = [
+            (VoteOption::Yes, 40),
+            (VoteOption::No, 30),
+            (VoteOption::NoWithVeto, 30),
+        ];
+        let res = end_poll_with_votes(Decimal::zero(), Decimal::percent(50), None, &votes, 0);
+        assert_end_poll_result(res, false, "Threshold not reached");
+    }
+
+    #[test]
+    fn query_poll_reports_result() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+        let (mut creator_env, creator_info) =
+            mock_info_height(TEST_CREATOR, &coins(2, VOTING_TOKEN), 0, 0);
+
+        let msg = create_poll_msg(0, "test".to_string(), None, Some(1));
+        execute(
+            deps.as_mut(),
+            creator_env.clone(),
+            creator_info.clone(),
+            msg,
+        )
+        .unwrap();
+
+        let info = mock_info(TEST_VOTER, &coins(10, VOTING_TOKEN));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::StakeVotingTokens {},
+        )
+        .unwrap();
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: VoteOption::NoWithVeto,
+            weight: Uint128::from(10u128),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+        let value: PollResponse = from_binary(&res).unwrap();
+        assert_eq!(PollStatus::InProgress, value.status);
+        assert_eq!(Uint128::from(10u128), value.veto_votes);
+        assert_eq!(None, value.rejected_reason);
+
+        creator_env.block.height = 2;
+        let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+        execute(deps.as_mut(), creator_env, creator_info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+        let value: PollResponse = from_binary(&res).unwrap();
+        assert_eq!(PollStatus::Rejected, value.status);
+        assert_eq!(default_veto_threshold(), value.veto_threshold);
+        assert_eq!(Some("Vetoed".to_string()), value.rejected_reason);
+    }
+
+    fn create_choice_poll_msg(options: &[&str], mode: Option<PollMode>) -> ExecuteMsg {
+        ExecuteMsg::CreatePoll {
+            quorum: Some(Decimal::percent(50)),
+            threshold: None,
+            veto_threshold: None,
+            description: "test".to_string(),
+            start_height: None,
+            end_height: Some(1),
+            options: Some(options.iter().map(|name| name.to_string()).collect()),
+            mode,
+        }
+    }
+
+    fn choice(option: u32, weight: u128) -> Choice {
+        Choice {
+            option,
+            weight: Uint128::from(weight),
+        }
+    }
+
+    // creates a multiple-choice poll, stakes and votes the given choices, each by
+    // another voter, and ends the poll
+    fn end_choice_poll_with_votes(mode: PollMode, votes: &[Vec<Choice>]) -> Response {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+        let (mut creator_env, creator_info) = mock_info_height(TEST_CREATOR, &[], 0, 0);
+
+        let msg = create_choice_poll_msg(&["red", "green", "blue"], Some(mode));
+        execute(
+            deps.as_mut(),
+            creator_env.clone(),
+            creator_info.clone(),
+            msg,
+        )
+        .unwrap();
+
+        for (i, choices) in votes.iter().enumerate() {
+            let weight = choices.iter().map(|choice| choice.weight.u128()).sum();
+            let info = mock_info(&format!("voter{}", i + 1), &coins(weight, VOTING_TOKEN));
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::StakeVotingTokens {},
+            )
+            .unwrap();
+            let msg = ExecuteMsg::CastChoiceVote {
+                poll_id: 1,
+                choices: choices.clone(),
+            };
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        creator_env.block.height = 2;
+        let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+        execute(deps.as_mut(), creator_env, creator_info, msg).unwrap()
+    }
+
+    #[test]
+    fn multiple_choice_poll_plurality() {
+        let votes = [
+            vec![choice(0, 30)],
+            vec![choice(2, 45)],
+            vec![choice(1, 25)],
+        ];
+        let res = end_choice_poll_with_votes(PollMode::SingleChoice, &votes);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "end_poll"),
+                attr("poll_id", "1"),
+                attr("rejected_reason", ""),
+                attr("passed", "true"),
+                attr("winning_option", "blue"),
+            ]
+        );
+
+        // the split weights of a voter add up per option
+        let votes = [
+            vec![choice(0, 30), choice(1, 20)],
+            vec![choice(1, 15), choice(2, 30)],
+        ];
+        let res = end_choice_poll_with_votes(PollMode::WeightedSplit, &votes);
+        assert_end_poll_result(res.clone(), true, "");
+        assert_eq!(res.attributes[4], attr("winning_option", "green"));
+
+        let votes = [
+            vec![choice(0, 30)],
+            vec![choice(2, 30)],
+            vec![choice(1, 10)],
+        ];
+        let res = end_choice_poll_with_votes(PollMode::SingleChoice, &votes);
+        assert_end_poll_result(res.clone(), false, "Tie");
+        assert_eq!(res.attributes[4], attr("winning_option", ""));
+    }
+
+    #[test]
+    fn multiple_choice_poll_quorum() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+        let (mut creator_env, creator_info) = mock_info_height(TEST_CREATOR, &[], 0, 0);
+
+        let msg = create_choice_poll_msg(&["red", "green"], Some(PollMode::WeightedSplit));
+        execute(
+            deps.as_mut(),
+            creator_env.clone(),
+            creator_info.clone(),
+            msg,
+        )
+        .unwrap();
+
+        let info = mock_info(TEST_VOTER, &coins(100, VOTING_TOKEN));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::StakeVotingTokens {},
+        )
+        .unwrap();
+        let msg = ExecuteMsg::CastChoiceVote {
+            poll_id: 1,
+            choices: vec![choice(0, 30), choice(1, 19)],
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+        let value: PollResponse = from_binary(&res).unwrap();
+        assert_eq!(PollMode::WeightedSplit, value.mode);
+        assert_eq!(
+            value.options,
+            vec![
+                PollOption {
+                    name: "red".to_string(),
+                    votes: Uint128::from(30u128),
+                },
+                PollOption {
+                    name: "green".to_string(),
+                    votes: Uint128::from(19u128),
+                },
+            ]
+        );
+
+        // 49 of 100 staked voted, below the quorum of 50%
+        creator_env.block.height = 2;
+        let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+        let res = execute(deps.as_mut(), creator_env, creator_info, msg).unwrap();
+        assert_end_poll_result(res, false, "Quorum not reached");
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+        let value: PollResponse = from_binary(&res).unwrap();
+        assert_eq!(PollStatus::Rejected, value.status);
+        assert_eq!(None, value.winning_option);
+    }
+
+    #[test]
+    fn fails_create_poll_invalid_options() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+        let info = mock_info(TEST_CREATOR, &[]);
+
+        for options in [vec!["red"], vec!["red", ""], vec!["red", "blue", "red"]] {
+            let msg = create_choice_poll_msg(&options, None);
+            match execute(deps.as_mut(), mock_env(), info.clone(), msg) {
+                Ok(_) => panic!("Must return error"),
+                Err(ContractError::PollInvalidOptions {
+                    min_options,
+                    max_options,
+                }) => {
+                    assert_eq!(min_options, 2);
+                    assert_eq!(max_options, 16);
+                }
+                Err(e) => panic!("Unexpected error: {:?}", e),
+            }
+        }
+
+        // a yes/no poll has no mode
+        let msg = ExecuteMsg::CreatePoll {
+            quorum: None,
+            threshold: None,
+            veto_threshold: None,
+            description: "test".to_string(),
+            start_height: None,
+            end_height: None,
+            options: None,
+            mode: Some(PollMode::WeightedSplit),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::PollModeWithoutOptions {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn fails_cast_overflowing_choice_vote() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+        let (env, info) = mock_info_height(TEST_CREATOR, &[], 0, 0);
+
+        let msg = create_choice_poll_msg(&["red", "green"], Some(PollMode::WeightedSplit));
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let voter_info = mock_info(TEST_VOTER, &coins(100, VOTING_TOKEN));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            voter_info.clone(),
+            ExecuteMsg::StakeVotingTokens {},
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::CastChoiceVote {
+            poll_id: 1,
+            choices: vec![choice(0, u128::MAX), choice(1, 1)],
+        };
+        match execute(deps.as_mut(), mock_env(), voter_info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::OverflowError(_)) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn fails_cast_invalid_choice_vote() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+        let (env, info) = mock_info_height(TEST_CREATOR, &[], 0, 0);
+
+        let msg = create_choice_poll_msg(&["red", "green"], None);
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let voter_info = mock_info(TEST_VOTER, &coins(100, VOTING_TOKEN));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            voter_info.clone(),
+            ExecuteMsg::StakeVotingTokens {},
+        )
+        .unwrap();
+
+        // a single choice poll does not take split votes, and options have to exist,
+        // be distinct and carry weight
+        for choices in [
+            vec![],
+            vec![choice(0, 10), choice(1, 10)],
+            vec![choice(2, 10)],
+            vec![choice(0, 0)],
+        ] {
+            let msg = ExecuteMsg::CastChoiceVote {
+                poll_id: 1,
+                choices,
+            };
+            match execute(deps.as_mut(), mock_env(), voter_info.clone(), msg) {
+                Ok(_) => panic!("Must return error"),
+                Err(ContractError::PollInvalidChoice {}) => {}
+                Err(e) => panic!("Unexpected error: {:?}", e),
+            }
+        }
+
+        // the weight of all choices has to be staked
+        let msg = ExecuteMsg::CastChoiceVote {
+            poll_id: 1,
+            choices: vec![choice(1, 101)],
+        };
+        match execute(deps.as_mut(), mock_env(), voter_info.clone(), msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::PollInsufficientStake {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // the vote has to match the type of the poll
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: VoteOption::Yes,
+            weight: Uint128::from(10u128),
+        };
+        match execute(deps.as_mut(), mock_env(), voter_info.clone(), msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::PollVoteMismatch {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let msg = create_poll_msg(0, "test".to_string(), None, None);
+        execute(deps.as_mut(), env, info, msg).unwrap();
+        let msg = ExecuteMsg::CastChoiceVote {
+            poll_id: 2,
+            choices: vec![choice(0, 10)],
+        };
+        match execute(deps.as_mut(), mock_env(), voter_info, msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::PollVoteMismatch {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    // creates a poll, stakes and votes the given weights, each by another voter, and
+    // ends the poll, idle tokens are staked but not voted
+    fn end_poll_with_votes(
+        quorum: Decimal,
+        threshold: Decimal,
+        veto_threshold: Option<Decimal>,
+        votes: &[(VoteOption, u128)],
+        idle: u128,
+    ) -> Response {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+        let (mut creator_env, creator_info) =
+            mock_info_height(TEST_CREATOR, &coins(2, VOTING_TOKEN), 0, 0);
+
+        let msg = ExecuteMsg::CreatePoll {
+            quorum: Some(quorum),
+            threshold: Some(threshold),
+            veto_threshold,
+            description: "test".to_string(),
+            start_height: None,
+            end_height: Some(creator_env.block.height + 1),
+            options: None,
+            mode: None,
+        };
+        execute(
+            deps.as_mut(),
+            creator_env.clone(),
+            creator_info.clone(),
+            msg,
+        )
+        .unwrap();
+
+        let idle_vote = (None, idle);
+        let votes = votes.iter().map(|&(vote, weight)| (Some(vote), weight));
+        for (i, (vote, weight)) in votes.chain(Some(idle_vote)).enumerate() {
+            if weight == 0 {
+                continue;
+            }
+            let info = mock_info(&format!("voter{}", i + 1), &coins(weight, VOTING_TOKEN));
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::StakeVotingTokens {},
+            )
+            .unwrap();
+            if let Some(vote) = vote {
+                let msg = ExecuteMsg::CastVote {
+                    poll_id: 1,
+                    vote,
+                    weight: Uint128::from(weight),
+                };
+                execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            }
+        }
+
+        creator_env.block.height += 1;
+        let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+        execute(deps.as_mut(), creator_env, creator_info, msg).unwrap()
+    }
+
+    fn default_veto_threshold() -> Decimal {
+        Decimal::from_ratio(334u128, 1000u128)
+    }
+
+    // the tallies reported after the result are not checked
+    fn assert_end_poll_result(execute_res: Response, passed: bool, rejected_reason: &str) {
+        assert_eq!(
+            execute_res.attributes[..4].to_vec(),
+            vec![
+                attr("action", "end_poll"),
+                attr("poll_id", "1"),
+                attr("rejected_reason", rejected_reason),
+                attr("passed", passed),
+            ]
+        );
+    }
+
     // helper to confirm the expected create_poll response
     fn assert_create_poll_result(
         poll_id: u64,
-        quorum: u8,
+        quorum: u64,
         end_height: u64,
         start_height: u64,
         creator: &str,
@@ -858,7 +1817,9 @@ mod tests {
                 attr("action", "create_poll"),
                 attr("creator", creator),
                 attr("poll_id", poll_id),
-                attr("quorum_percentage", quorum),
+                attr("quorum", Decimal::percent(quorum)),
+                attr("threshold", Decimal::percent(50)),
+                attr("veto_threshold", default_veto_threshold()),
                 attr("end_height", end_height),
                 attr("start_height", start_height),
             ]
@@ -873,6 +1834,9 @@ mod tests {
                 owner: Addr::unchecked(TEST_CREATOR),
                 poll_count: 1,
                 staked_tokens: Uint128::zero(),
+                quorum: Decimal::zero(),
+                threshold: Decimal::percent(50),
+                veto_threshold: default_veto_threshold(),
             }
         );
     }
@@ -893,6 +1857,9 @@ mod tests {
                 owner: Addr::unchecked(TEST_CREATOR),
                 poll_count: poll_count.unwrap_or_default(),
                 staked_tokens: Uint128::from(staked_tokens),
+                quorum: Decimal::zero(),
+                threshold: Decimal::percent(50),
+                veto_threshold: default_veto_threshold(),
             }
         );
     }